@@ -5,6 +5,7 @@ extern crate test;
 
 use test::Bencher;
 use bytes::Buf;
+use bytes::buf::ArrayBuf;
 
 /// Dummy Buf implementation
 struct TestBuf {
@@ -129,13 +130,13 @@ macro_rules! bench {
             })
         }
     );
-    ($fname:ident, option) => (
+    ($fname:ident, array) => (
         #[bench]
         fn $fname(b: &mut Bencher) {
             let data = [1u8; 1];
             b.iter(|| {
                 for _ in 0..8 {
-                    let mut buf = Some(data);
+                    let mut buf = ArrayBuf::new(data);
                     let buf = &mut buf as &mut dyn Buf; // type erasure
                     test::black_box(buf.get_u8());
                 }
@@ -159,7 +160,7 @@ macro_rules! bench_group {
 mod get_u8 {
     use super::*;
     bench_group!(get_u8);
-    bench!(option, option);
+    bench!(array, array);
 }
 mod get_u16 {
     use super::*;
@@ -0,0 +1,89 @@
+use crate::Buf;
+
+/// A `Buf` that owns a fixed-size byte array.
+///
+/// This is the `Buf` type that owned arrays, as well as `u8` and `i8`, are
+/// converted into by [`IntoBuf`]. It keeps track of how many bytes of the
+/// array have been read.
+///
+/// [`IntoBuf`]: trait.IntoBuf.html
+///
+/// # Examples
+///
+/// ```
+/// use bytes::{Buf, IntoBuf};
+///
+/// let mut buf = [1, 2, 3, 4].into_buf();
+///
+/// assert_eq!(0x0102, buf.get_u16());
+/// assert_eq!(2, buf.remaining());
+/// assert_eq!(&[3, 4], buf.bytes());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ArrayBuf<const N: usize> {
+    array: [u8; N],
+    pos: usize,
+}
+
+impl<const N: usize> ArrayBuf<N> {
+    /// Creates a new `ArrayBuf` positioned at the start of `array`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Buf;
+    /// use bytes::buf::ArrayBuf;
+    ///
+    /// let buf = ArrayBuf::new([0; 32]);
+    /// assert_eq!(32, buf.remaining());
+    /// ```
+    pub fn new(array: [u8; N]) -> ArrayBuf<N> {
+        ArrayBuf {
+            array: array,
+            pos: 0,
+        }
+    }
+
+    /// Consumes this `ArrayBuf`, returning the underlying array.
+    ///
+    /// The returned array includes the bytes that have already been read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Buf;
+    /// use bytes::buf::ArrayBuf;
+    ///
+    /// let mut buf = ArrayBuf::new([1, 2, 3]);
+    /// buf.advance(2);
+    ///
+    /// assert_eq!([1, 2, 3], buf.into_inner());
+    /// ```
+    pub fn into_inner(self) -> [u8; N] {
+        self.array
+    }
+}
+
+impl<const N: usize> Buf for ArrayBuf<N> {
+    #[inline]
+    fn remaining(&self) -> usize {
+        N - self.pos
+    }
+
+    #[inline]
+    fn bytes(&self) -> &[u8] {
+        &self.array[self.pos..]
+    }
+
+    #[inline]
+    fn advance(&mut self, cnt: usize) {
+        assert!(cnt <= self.remaining(), "cannot advance past `remaining`");
+        self.pos += cnt;
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for ArrayBuf<N> {
+    fn from(array: [u8; N]) -> ArrayBuf<N> {
+        ArrayBuf::new(array)
+    }
+}
@@ -1,6 +1,15 @@
-use super::{IntoBuf, Take, Reader, FromBuf, Chain};
+use super::{IntoBuf, Take, FromBuf, Chain, LenPrefix, PrefixedError, VarintError};
+use super::{prefixed, varint};
+use crate::{Bytes, BytesMut};
+#[cfg(feature = "std")]
+use super::Reader;
 
-use std::{cmp, io::IoSlice, ptr, mem};
+use core::{cmp, fmt, ptr, mem};
+
+use alloc::boxed::Box;
+
+#[cfg(feature = "std")]
+use std::io::IoSlice;
 
 macro_rules! buf_get_impl {
     ($this:ident, $typ:tt::$conv:tt) => ({
@@ -41,6 +50,29 @@ macro_rules! buf_get_impl {
     }};
 }
 
+macro_rules! buf_peek_impl {
+    ($this:ident, $typ:tt::$conv:tt) => ({
+        let mut buf = [0; (mem::size_of::<$typ>())];
+        $this.peek_slice(&mut buf);
+        $typ::$conv(buf)
+    });
+}
+
+macro_rules! buf_try_get_impl {
+    ($this:ident, $size:expr, $get:ident $(, $arg:expr)*) => {{
+        let available = $this.remaining();
+
+        if available < $size {
+            return Err(TryGetError {
+                requested: $size,
+                available: available,
+            });
+        }
+
+        Ok($this.$get($($arg),*))
+    }};
+}
+
 /// Read bytes from a buffer.
 ///
 /// A buffer stores bytes in memory such that read operations are infallible.
@@ -121,6 +153,49 @@ pub trait Buf {
     /// empty slice.
     fn bytes(&self) -> &[u8];
 
+    /// Returns a slice starting `offset` bytes past the current position and
+    /// of length between 0 and `Buf::remaining() - offset`, without advancing
+    /// the cursor.
+    ///
+    /// This is the same as `bytes` when `offset` is 0. Calling it with the
+    /// offsets following each returned slice walks the whole buffer without
+    /// consuming it, which is how `peek_slice` and `get_cstr` read data that
+    /// spans several chunks.
+    ///
+    /// This is a lower level function. Most operations are done with other
+    /// functions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, Bytes};
+    ///
+    /// let buf = Bytes::from_static(b"hello ").chain(Bytes::from_static(b"world"));
+    ///
+    /// assert_eq!(buf.bytes_at(0), &b"hello "[..]);
+    /// assert_eq!(buf.bytes_at(2), &b"llo "[..]);
+    /// assert_eq!(buf.bytes_at(6), &b"world"[..]);
+    /// assert_eq!(buf.bytes_at(11), &b""[..]);
+    /// ```
+    ///
+    /// # Implementer notes
+    ///
+    /// This function should never panic. Once `offset` reaches
+    /// `Buf::remaining`, an empty slice should be returned.
+    ///
+    /// The default implementation only looks at the slice returned by
+    /// `bytes`, and returns an empty slice past its end. Implementations whose
+    /// data is not stored contiguously should override this function.
+    fn bytes_at(&self, offset: usize) -> &[u8] {
+        let bytes = self.bytes();
+
+        if offset < bytes.len() {
+            &bytes[offset..]
+        } else {
+            &[]
+        }
+    }
+
     /// Fills `dst` with potentially multiple slices starting at `self`'s
     /// current position.
     ///
@@ -148,6 +223,7 @@ pub trait Buf {
     /// with `dst` being a zero length slice.
     ///
     /// [`writev`]: http://man7.org/linux/man-pages/man2/readv.2.html
+    #[cfg(feature = "std")]
     fn bytes_vectored<'a>(&'a self, dst: &mut [IoSlice<'a>]) -> usize {
         if dst.is_empty() {
             return 0;
@@ -257,6 +333,53 @@ pub trait Buf {
         }
     }
 
+    /// Consumes `len` bytes from `self` and returns them as a `Bytes`.
+    ///
+    /// The default implementation copies the bytes into a new buffer.
+    /// Buffers that are backed by shared memory, such as `Bytes` and
+    /// `BytesMut`, return a handle to that memory instead and do not copy.
+    /// `Chain` and `Take` forward to the underlying buffers, so they do not
+    /// copy either as long as the requested bytes lie in a single one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, Bytes};
+    ///
+    /// let mut buf = Bytes::from(&b"hello world, this is a long message"[..]);
+    /// let ptr = buf.as_ptr();
+    ///
+    /// let hello = buf.copy_to_bytes(5);
+    /// assert_eq!(&hello[..], b"hello");
+    /// assert_eq!(hello.as_ptr(), ptr);
+    ///
+    /// let mut slice = &b"hello world"[..];
+    /// assert_eq!(&slice.copy_to_bytes(5)[..], b"hello");
+    /// assert_eq!(slice, b" world");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `len > self.remaining()`.
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        assert!(len <= self.remaining(), "`len` greater than remaining");
+
+        let mut ret = BytesMut::with_capacity(len);
+
+        while ret.len() < len {
+            let cnt = {
+                let src = self.bytes();
+                let cnt = cmp::min(src.len(), len - ret.len());
+                ret.extend_from_slice(&src[..cnt]);
+                cnt
+            };
+
+            self.advance(cnt);
+        }
+
+        ret.freeze()
+    }
+
     /// Gets an unsigned 8 bit integer from `self`.
     ///
     /// The current position is advanced by 1.
@@ -787,214 +910,1378 @@ pub trait Buf {
         f64::from_bits(Self::get_u64_le(self))
     }
 
-    /// Transforms a `Buf` into a concrete buffer.
+    /// Gets a fixed-size array of `N` bytes from `self`.
     ///
-    /// `collect()` can operate on any value that implements `Buf`, and turn it
-    /// into the relevant concrete buffer type.
+    /// This is convenient for reading fixed-size fields such as hashes, keys
+    /// and nonces. The bytes may span several chunks.
     ///
-    /// # Examples
+    /// The current position is advanced by `N`.
     ///
-    /// Collecting a buffer and loading the contents into a `Vec<u8>`.
+    /// # Examples
     ///
     /// ```
     /// use bytes::Buf;
     ///
-    /// let buf = &b"hello world"[..];
-    /// let vec: Vec<u8> = buf.collect();
+    /// let mut buf = &b"\x01\x02\x03\x04 hello"[..];
+    /// let key: [u8; 4] = buf.get_array();
     ///
-    /// assert_eq!(vec, b"hello world");
+    /// assert_eq!(key, [1, 2, 3, 4]);
+    /// assert_eq!(6, buf.remaining());
     /// ```
-    fn collect<B>(self) -> B
-        where Self: Sized,
-              B: FromBuf,
-    {
-        B::from_buf(self)
+    ///
+    /// # Panics
+    ///
+    /// This function panics if there is not enough remaining data in `self`.
+    fn get_array<const N: usize>(&mut self) -> [u8; N] where Self: Sized {
+        let mut dst = [0; N];
+        self.copy_to_slice(&mut dst);
+        dst
     }
 
-    /// Creates an adaptor which will read at most `limit` bytes from `self`.
+    /// Gets an unsigned LEB128 varint of at most 64 bits from `self`, as used
+    /// by protocol buffers.
     ///
-    /// This function returns a new instance of `Buf` which will read at most
-    /// `limit` bytes.
+    /// The current position is advanced by the length of the varint on
+    /// success. The varint may span several chunks. On error, the position is
+    /// left unchanged.
     ///
     /// # Examples
     ///
     /// ```
-    /// use bytes::{Buf, BufMut};
+    /// use bytes::Buf;
     ///
-    /// let mut buf = b"hello world"[..].take(5);
-    /// let mut dst = vec![];
+    /// let mut buf = &b"\xac\x02 hello"[..];
+    /// assert_eq!(Ok(300), buf.get_uvarint());
+    /// assert_eq!(6, buf.remaining());
+    /// ```
     ///
-    /// dst.put(&mut buf);
-    /// assert_eq!(dst, b"hello");
+    /// # Errors
     ///
-    /// let mut buf = buf.into_inner();
-    /// dst.clear();
-    /// dst.put(&mut buf);
-    /// assert_eq!(dst, b" world");
-    /// ```
-    fn take(self, limit: usize) -> Take<Self>
-        where Self: Sized
-    {
-        super::take::new(self, limit)
+    /// Returns `VarintError::Truncated` if `self` ends before the varint does,
+    /// and `VarintError::Overflow` if the value does not fit in a `u64`.
+    fn get_uvarint(&mut self) -> Result<u64, VarintError> {
+        let (value, len) = varint::peek(self, 64)?;
+        self.advance(len);
+        Ok(value)
     }
 
-    /// Creates an adaptor which will chain this buffer with another.
+    /// Gets an unsigned LEB128 varint of at most 32 bits from `self`.
     ///
-    /// The returned `Buf` instance will first consume all bytes from `self`.
-    /// Afterwards the output is equivalent to the output of next.
+    /// The current position is advanced by the length of the varint on
+    /// success. On error, the position is left unchanged.
     ///
     /// # Examples
     ///
     /// ```
     /// use bytes::Buf;
     ///
-    /// let chain = b"hello "[..].chain(&b"world"[..]);
-    ///
-    /// let full: Vec<u8> = chain.collect();
-    /// assert_eq!(full, b"hello world");
+    /// let mut buf = &b"\xff\xff\xff\xff\x0f hello"[..];
+    /// assert_eq!(Ok(u32::max_value()), buf.get_uvarint32());
     /// ```
-    fn chain<U>(self, next: U) -> Chain<Self, U::Buf>
-        where U: IntoBuf,
-              Self: Sized,
-    {
-        Chain::new(self, next.into_buf())
+    ///
+    /// # Errors
+    ///
+    /// Returns `VarintError::Truncated` if `self` ends before the varint does,
+    /// and `VarintError::Overflow` if the value does not fit in a `u32`.
+    fn get_uvarint32(&mut self) -> Result<u32, VarintError> {
+        let (value, len) = varint::peek(self, 32)?;
+        self.advance(len);
+        Ok(value as u32)
     }
 
-    /// Creates a "by reference" adaptor for this instance of `Buf`.
+    /// Gets a zigzag encoded signed varint of at most 64 bits from `self`, as
+    /// used by the protocol buffers `sint64` type.
     ///
-    /// The returned adaptor also implements `Buf` and will simply borrow `self`.
+    /// The current position is advanced by the length of the varint on
+    /// success. On error, the position is left unchanged.
     ///
     /// # Examples
     ///
     /// ```
-    /// use bytes::{Buf, BufMut};
+    /// use bytes::Buf;
     ///
-    /// let mut buf = &b"hello world"[..];
-    /// let mut dst = vec![];
+    /// let mut buf = &b"\x03 hello"[..];
+    /// assert_eq!(Ok(-2), buf.get_ivarint());
+    /// ```
     ///
-    /// {
-    ///     let mut reference = buf.by_ref();
-    ///     dst.put(&mut reference.take(5));
-    ///     assert_eq!(dst, &b"hello"[..]);
-    /// } // drop our &mut reference so we can use `buf` again
+    /// # Errors
     ///
-    /// dst.clear();
-    /// dst.put(&mut buf);
-    /// assert_eq!(dst, &b" world"[..]);
-    /// ```
-    fn by_ref(&mut self) -> &mut Self where Self: Sized {
-        self
+    /// Returns `VarintError::Truncated` if `self` ends before the varint does,
+    /// and `VarintError::Overflow` if the value does not fit in an `i64`.
+    fn get_ivarint(&mut self) -> Result<i64, VarintError> {
+        self.get_uvarint().map(varint::zigzag_decode)
     }
 
-    /// Creates an adaptor which implements the `Read` trait for `self`.
+    /// Gets a zigzag encoded signed varint of at most 32 bits from `self`, as
+    /// used by the protocol buffers `sint32` type.
     ///
-    /// This function returns a new value which implements `Read` by adapting
-    /// the `Read` trait functions to the `Buf` trait functions. Given that
-    /// `Buf` operations are infallible, none of the `Read` functions will
-    /// return with `Err`.
+    /// The current position is advanced by the length of the varint on
+    /// success. On error, the position is left unchanged.
     ///
     /// # Examples
     ///
     /// ```
-    /// use bytes::{Buf, IntoBuf, Bytes};
-    /// use std::io::Read;
+    /// use bytes::Buf;
     ///
-    /// let buf = Bytes::from("hello world").into_buf();
+    /// let mut buf = &b"\xff\xff\xff\xff\x0f hello"[..];
+    /// assert_eq!(Ok(i32::min_value()), buf.get_ivarint32());
+    /// ```
     ///
-    /// let mut reader = buf.reader();
-    /// let mut dst = [0; 1024];
+    /// # Errors
     ///
-    /// let num = reader.read(&mut dst).unwrap();
+    /// Returns `VarintError::Truncated` if `self` ends before the varint does,
+    /// and `VarintError::Overflow` if the value does not fit in an `i32`.
+    fn get_ivarint32(&mut self) -> Result<i32, VarintError> {
+        self.get_uvarint32().map(|n| varint::zigzag_decode(n as u64) as i32)
+    }
+
+    /// Gets a length-prefixed field from `self`.
+    ///
+    /// The length is read using `prefix`, and the field is returned as a
+    /// `Bytes` using `copy_to_bytes`, so no data is copied when `self` is
+    /// backed by shared memory. The current position is advanced past the
+    /// prefix and the field on success. On error, the position is left
+    /// unchanged.
+    ///
+    /// # Examples
     ///
-    /// assert_eq!(11, num);
-    /// assert_eq!(&dst[..11], &b"hello world"[..]);
     /// ```
-    fn reader(self) -> Reader<Self> where Self: Sized {
-        super::reader::new(self)
+    /// use bytes::Buf;
+    /// use bytes::buf::{LenPrefix, PrefixedError};
+    ///
+    /// let mut buf = &b"\x05hello\x05wor"[..];
+    /// assert_eq!(buf.get_prefixed_bytes(LenPrefix::U8).unwrap(), &b"hello"[..]);
+    /// assert_eq!(buf.get_prefixed_bytes(LenPrefix::U8), Err(PrefixedError::Truncated));
+    /// assert_eq!(buf.remaining(), 4);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `PrefixedError::Truncated` if `self` ends before the end of the
+    /// field, and `PrefixedError::InvalidPrefix` if a varint prefix is
+    /// malformed. The declared length is not limited, see
+    /// `get_prefixed_bytes_limited` to reject oversized fields.
+    fn get_prefixed_bytes(&mut self, prefix: LenPrefix) -> Result<Bytes, PrefixedError> {
+        self.get_prefixed_bytes_limited(prefix, usize::max_value())
     }
-}
 
-impl<T: Buf + ?Sized> Buf for &mut T {
-    fn remaining(&self) -> usize {
-        (**self).remaining()
-    }
+    /// Gets a length-prefixed field from `self`, rejecting fields whose
+    /// declared length exceeds `limit`.
+    ///
+    /// This behaves like `get_prefixed_bytes`, except that the declared length
+    /// is checked against `limit` before anything else.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Buf;
+    /// use bytes::buf::{LenPrefix, PrefixedError};
+    ///
+    /// let mut buf = &b"\x00\x00\x10\x00hello"[..];
+    /// assert_eq!(
+    ///     buf.get_prefixed_bytes_limited(LenPrefix::U32Be, 1024),
+    ///     Err(PrefixedError::TooLong { len: 4096, limit: 1024 }));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `PrefixedError::TooLong` if the declared length exceeds
+    /// `limit`, in addition to the errors returned by `get_prefixed_bytes`.
+    fn get_prefixed_bytes_limited(&mut self, prefix: LenPrefix, limit: usize)
+        -> Result<Bytes, PrefixedError>
+    {
+        let (len, header) = prefix.peek_len(self)?;
 
-    fn bytes(&self) -> &[u8] {
-        (**self).bytes()
-    }
+        if len > limit as u64 {
+            return Err(PrefixedError::TooLong {
+                len: len,
+                limit: limit,
+            });
+        }
 
-    fn bytes_vectored<'b>(&'b self, dst: &mut [IoSlice<'b>]) -> usize {
-        (**self).bytes_vectored(dst)
-    }
+        if len > (self.remaining() - header) as u64 {
+            return Err(PrefixedError::Truncated);
+        }
 
-    fn advance(&mut self, cnt: usize) {
-        (**self).advance(cnt)
+        self.advance(header);
+        Ok(self.copy_to_bytes(len as usize))
     }
-}
 
-impl<T: Buf + ?Sized> Buf for Box<T> {
-    fn remaining(&self) -> usize {
-        (**self).remaining()
+    /// Gets a NUL-terminated string from `self`.
+    ///
+    /// The returned `Bytes` does not include the terminator. The current
+    /// position is advanced past the terminator on success. On error, the
+    /// position is left unchanged.
+    ///
+    /// The terminator is searched for in all of the remaining data. When the
+    /// data comes from an untrusted peer, use `get_cstr_limited` to bound the
+    /// search.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Buf;
+    /// use bytes::buf::PrefixedError;
+    ///
+    /// let mut buf = &b"hello\0world"[..];
+    /// assert_eq!(buf.get_cstr().unwrap(), &b"hello"[..]);
+    /// assert_eq!(buf.get_cstr(), Err(PrefixedError::Unterminated));
+    /// assert_eq!(buf, b"world");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `PrefixedError::Unterminated` if there is no NUL byte in the
+    /// remaining data.
+    fn get_cstr(&mut self) -> Result<Bytes, PrefixedError> {
+        match prefixed::find_nul(self, self.remaining()) {
+            Some(len) => {
+                let ret = self.copy_to_bytes(len);
+                self.advance(1);
+                Ok(ret)
+            }
+            None => Err(PrefixedError::Unterminated),
+        }
     }
 
-    fn bytes(&self) -> &[u8] {
-        (**self).bytes()
-    }
+    /// Gets a NUL-terminated string of at most `limit` bytes from `self`.
+    ///
+    /// This is like `get_cstr`, but only the first `limit + 1` bytes are
+    /// searched for the terminator, so a peer that never sends one cannot
+    /// make the search, or the returned `Bytes`, grow without bound.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Buf;
+    /// use bytes::buf::PrefixedError;
+    ///
+    /// let mut buf = &b"abc\0hello\0"[..];
+    /// assert_eq!(buf.get_cstr_limited(3).unwrap(), &b"abc"[..]);
+    /// assert_eq!(buf.get_cstr_limited(3),
+    ///     Err(PrefixedError::TooLong { len: 4, limit: 3 }));
+    /// assert_eq!(buf, b"hello\0");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `PrefixedError::TooLong` if none of the first `limit + 1`
+    /// bytes is a NUL byte, and `PrefixedError::Unterminated` if the
+    /// remaining data is shorter than that and has no NUL byte.
+    fn get_cstr_limited(&mut self, limit: usize) -> Result<Bytes, PrefixedError> {
+        let max = limit.saturating_add(1);
 
-    fn bytes_vectored<'b>(&'b self, dst: &mut [IoSlice<'b>]) -> usize {
-        (**self).bytes_vectored(dst)
+        match prefixed::find_nul(self, max) {
+            Some(len) => {
+                let ret = self.copy_to_bytes(len);
+                self.advance(1);
+                Ok(ret)
+            }
+            None if self.remaining() >= max => Err(PrefixedError::TooLong {
+                len: max as u64,
+                limit: limit,
+            }),
+            None => Err(PrefixedError::Unterminated),
+        }
     }
 
-    fn advance(&mut self, cnt: usize) {
-        (**self).advance(cnt)
-    }
-}
+    /// Copies bytes from `self` into `dst` without advancing the cursor.
+    ///
+    /// Unlike `copy_to_slice`, the bytes may be read again afterwards. This is
+    /// useful to inspect a header before deciding whether to consume it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, Bytes};
+    ///
+    /// let buf = Bytes::from_static(b"hel").chain(Bytes::from_static(b"lo world"));
+    /// let mut dst = [0; 5];
+    ///
+    /// buf.peek_slice(&mut dst);
+    /// assert_eq!(&b"hello"[..], &dst);
+    /// assert_eq!(11, buf.remaining());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `self.remaining() < dst.len()`.
+    ///
+    /// # Implementer notes
+    ///
+    /// The default implementation reads the chunks returned by `bytes_at`. If
+    /// they end early, it falls back to the slices returned by
+    /// `bytes_vectored`, and panics if those do not cover `dst` either.
+    fn peek_slice(&self, dst: &mut [u8]) {
+        assert!(self.remaining() >= dst.len(), "cannot peek past `remaining`");
 
-impl Buf for &[u8] {
-    #[inline]
-    fn remaining(&self) -> usize {
-        self.len()
-    }
+        let mut off = 0;
 
-    #[inline]
-    fn bytes(&self) -> &[u8] {
-        self
-    }
+        while off < dst.len() {
+            let src = self.bytes_at(off);
 
-    #[inline]
-    fn advance(&mut self, cnt: usize) {
-        *self = &self[cnt..];
-    }
-}
+            if src.is_empty() {
+                break;
+            }
 
-impl Buf for Option<[u8; 1]> {
-    fn remaining(&self) -> usize {
-        if self.is_some() {
-            1
-        } else {
-            0
+            let cnt = cmp::min(src.len(), dst.len() - off);
+
+            dst[off..off + cnt].copy_from_slice(&src[..cnt]);
+            off += cnt;
+        }
+
+        if off < dst.len() {
+            assert!(
+                peek_vectored(self, dst),
+                "`peek_slice` is not supported across chunks by this `Buf`"
+            );
         }
     }
 
+    /// Gets an unsigned 8 bit integer from `self` without advancing the
+    /// cursor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Buf;
+    ///
+    /// let buf = &b"\x08 hello"[..];
+    /// assert_eq!(8, buf.peek_u8());
+    /// assert_eq!(7, buf.remaining());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if there is no more remaining data in `self`.
+    fn peek_u8(&self) -> u8 {
+        assert!(self.remaining() >= 1, "cannot peek past `remaining`");
+        self.bytes()[0]
+    }
+
+    /// Gets an unsigned 16 bit integer from `self` in big-endian byte order
+    /// without advancing the cursor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Buf;
+    ///
+    /// let buf = &b"\x08\x09 hello"[..];
+    /// assert_eq!(0x0809, buf.peek_u16());
+    /// assert_eq!(8, buf.remaining());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if there is not enough remaining data in `self`.
+    fn peek_u16(&self) -> u16 {
+        buf_peek_impl!(self, u16::from_be_bytes)
+    }
+
+    /// Gets an unsigned 16 bit integer from `self` in little-endian byte order
+    /// without advancing the cursor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Buf;
+    ///
+    /// let buf = &b"\x09\x08 hello"[..];
+    /// assert_eq!(0x0809, buf.peek_u16_le());
+    /// assert_eq!(8, buf.remaining());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if there is not enough remaining data in `self`.
+    fn peek_u16_le(&self) -> u16 {
+        buf_peek_impl!(self, u16::from_le_bytes)
+    }
+
+    /// Gets an unsigned 32 bit integer from `self` in big-endian byte order
+    /// without advancing the cursor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Buf;
+    ///
+    /// let buf = &b"\x08\x09\xA0\xA1 hello"[..];
+    /// assert_eq!(0x0809A0A1, buf.peek_u32());
+    /// assert_eq!(10, buf.remaining());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if there is not enough remaining data in `self`.
+    fn peek_u32(&self) -> u32 {
+        buf_peek_impl!(self, u32::from_be_bytes)
+    }
+
+    /// Gets an unsigned 32 bit integer from `self` in little-endian byte order
+    /// without advancing the cursor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Buf;
+    ///
+    /// let buf = &b"\xA1\xA0\x09\x08 hello"[..];
+    /// assert_eq!(0x0809A0A1, buf.peek_u32_le());
+    /// assert_eq!(10, buf.remaining());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if there is not enough remaining data in `self`.
+    fn peek_u32_le(&self) -> u32 {
+        buf_peek_impl!(self, u32::from_le_bytes)
+    }
+
+    /// Gets an unsigned 64 bit integer from `self` in big-endian byte order
+    /// without advancing the cursor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Buf;
+    ///
+    /// let buf = &b"\x01\x02\x03\x04\x05\x06\x07\x08 hello"[..];
+    /// assert_eq!(0x0102030405060708, buf.peek_u64());
+    /// assert_eq!(14, buf.remaining());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if there is not enough remaining data in `self`.
+    fn peek_u64(&self) -> u64 {
+        buf_peek_impl!(self, u64::from_be_bytes)
+    }
+
+    /// Gets an unsigned 64 bit integer from `self` in little-endian byte order
+    /// without advancing the cursor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Buf;
+    ///
+    /// let buf = &b"\x08\x07\x06\x05\x04\x03\x02\x01 hello"[..];
+    /// assert_eq!(0x0102030405060708, buf.peek_u64_le());
+    /// assert_eq!(14, buf.remaining());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if there is not enough remaining data in `self`.
+    fn peek_u64_le(&self) -> u64 {
+        buf_peek_impl!(self, u64::from_le_bytes)
+    }
+
+    /// Copies bytes from `self` into `dst`.
+    ///
+    /// The cursor is advanced by the number of bytes copied on success. If
+    /// `self` does not have enough remaining bytes to fill `dst`, an error is
+    /// returned and neither the cursor nor `dst` are modified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, TryGetError};
+    ///
+    /// let mut buf = &b"hello world"[..];
+    /// let mut dst = [0; 5];
+    ///
+    /// assert_eq!(Ok(()), buf.try_copy_to_slice(&mut dst));
+    /// assert_eq!(&b"hello"[..], &dst);
+    /// assert_eq!(6, buf.remaining());
+    ///
+    /// let mut dst = [0; 10];
+    /// assert_eq!(Err(TryGetError { requested: 10, available: 6 }), buf.try_copy_to_slice(&mut dst));
+    /// assert_eq!(6, buf.remaining());
+    /// ```
+    fn try_copy_to_slice(&mut self, dst: &mut [u8]) -> Result<(), TryGetError> {
+        buf_try_get_impl!(self, dst.len(), copy_to_slice, dst)
+    }
+
+    /// Gets an unsigned 8 bit integer from `self`.
+    ///
+    /// The current position is advanced by 1 on success. If there are fewer
+    /// than 1 remaining bytes, an error is returned and the position is left
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, TryGetError};
+    ///
+    /// let mut buf = &b"\x08 hello"[..];
+    /// assert_eq!(Ok(8), buf.try_get_u8());
+    ///
+    /// let mut buf = &b""[..];
+    /// assert_eq!(Err(TryGetError { requested: 1, available: 0 }), buf.try_get_u8());
+    /// ```
+    fn try_get_u8(&mut self) -> Result<u8, TryGetError> {
+        buf_try_get_impl!(self, 1, get_u8)
+    }
+
+    /// Gets a signed 8 bit integer from `self`.
+    ///
+    /// The current position is advanced by 1 on success. If there are fewer
+    /// than 1 remaining bytes, an error is returned and the position is left
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, TryGetError};
+    ///
+    /// let mut buf = &b"\x08 hello"[..];
+    /// assert_eq!(Ok(8), buf.try_get_i8());
+    ///
+    /// let mut buf = &b""[..];
+    /// assert_eq!(Err(TryGetError { requested: 1, available: 0 }), buf.try_get_i8());
+    /// ```
+    fn try_get_i8(&mut self) -> Result<i8, TryGetError> {
+        buf_try_get_impl!(self, 1, get_i8)
+    }
+
+    /// Gets an unsigned 16 bit integer from `self` in big-endian byte order.
+    ///
+    /// The current position is advanced by 2 on success. If there are fewer
+    /// than 2 remaining bytes, an error is returned and the position is left
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, TryGetError};
+    ///
+    /// let mut buf = &b"\x08\x09 hello"[..];
+    /// assert_eq!(Ok(0x0809), buf.try_get_u16());
+    ///
+    /// let mut buf = &b"\x08"[..];
+    /// assert_eq!(Err(TryGetError { requested: 2, available: 1 }), buf.try_get_u16());
+    /// assert_eq!(1, buf.remaining());
+    /// ```
+    fn try_get_u16(&mut self) -> Result<u16, TryGetError> {
+        buf_try_get_impl!(self, 2, get_u16)
+    }
+
+    /// Gets an unsigned 16 bit integer from `self` in little-endian byte order.
+    ///
+    /// The current position is advanced by 2 on success. If there are fewer
+    /// than 2 remaining bytes, an error is returned and the position is left
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, TryGetError};
+    ///
+    /// let mut buf = &b"\x09\x08 hello"[..];
+    /// assert_eq!(Ok(0x0809), buf.try_get_u16_le());
+    ///
+    /// let mut buf = &b"\x08"[..];
+    /// assert_eq!(Err(TryGetError { requested: 2, available: 1 }), buf.try_get_u16_le());
+    /// assert_eq!(1, buf.remaining());
+    /// ```
+    fn try_get_u16_le(&mut self) -> Result<u16, TryGetError> {
+        buf_try_get_impl!(self, 2, get_u16_le)
+    }
+
+    /// Gets a signed 16 bit integer from `self` in big-endian byte order.
+    ///
+    /// The current position is advanced by 2 on success. If there are fewer
+    /// than 2 remaining bytes, an error is returned and the position is left
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, TryGetError};
+    ///
+    /// let mut buf = &b"\x08\x09 hello"[..];
+    /// assert_eq!(Ok(0x0809), buf.try_get_i16());
+    ///
+    /// let mut buf = &b"\x08"[..];
+    /// assert_eq!(Err(TryGetError { requested: 2, available: 1 }), buf.try_get_i16());
+    /// assert_eq!(1, buf.remaining());
+    /// ```
+    fn try_get_i16(&mut self) -> Result<i16, TryGetError> {
+        buf_try_get_impl!(self, 2, get_i16)
+    }
+
+    /// Gets a signed 16 bit integer from `self` in little-endian byte order.
+    ///
+    /// The current position is advanced by 2 on success. If there are fewer
+    /// than 2 remaining bytes, an error is returned and the position is left
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, TryGetError};
+    ///
+    /// let mut buf = &b"\x09\x08 hello"[..];
+    /// assert_eq!(Ok(0x0809), buf.try_get_i16_le());
+    ///
+    /// let mut buf = &b"\x08"[..];
+    /// assert_eq!(Err(TryGetError { requested: 2, available: 1 }), buf.try_get_i16_le());
+    /// assert_eq!(1, buf.remaining());
+    /// ```
+    fn try_get_i16_le(&mut self) -> Result<i16, TryGetError> {
+        buf_try_get_impl!(self, 2, get_i16_le)
+    }
+
+    /// Gets an unsigned 32 bit integer from `self` in the big-endian byte order.
+    ///
+    /// The current position is advanced by 4 on success. If there are fewer
+    /// than 4 remaining bytes, an error is returned and the position is left
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, TryGetError};
+    ///
+    /// let mut buf = &b"\x08\x09\xA0\xA1 hello"[..];
+    /// assert_eq!(Ok(0x0809A0A1), buf.try_get_u32());
+    ///
+    /// let mut buf = &b"\x08"[..];
+    /// assert_eq!(Err(TryGetError { requested: 4, available: 1 }), buf.try_get_u32());
+    /// assert_eq!(1, buf.remaining());
+    /// ```
+    fn try_get_u32(&mut self) -> Result<u32, TryGetError> {
+        buf_try_get_impl!(self, 4, get_u32)
+    }
+
+    /// Gets an unsigned 32 bit integer from `self` in the little-endian byte order.
+    ///
+    /// The current position is advanced by 4 on success. If there are fewer
+    /// than 4 remaining bytes, an error is returned and the position is left
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, TryGetError};
+    ///
+    /// let mut buf = &b"\xA1\xA0\x09\x08 hello"[..];
+    /// assert_eq!(Ok(0x0809A0A1), buf.try_get_u32_le());
+    ///
+    /// let mut buf = &b"\x08"[..];
+    /// assert_eq!(Err(TryGetError { requested: 4, available: 1 }), buf.try_get_u32_le());
+    /// assert_eq!(1, buf.remaining());
+    /// ```
+    fn try_get_u32_le(&mut self) -> Result<u32, TryGetError> {
+        buf_try_get_impl!(self, 4, get_u32_le)
+    }
+
+    /// Gets a signed 32 bit integer from `self` in big-endian byte order.
+    ///
+    /// The current position is advanced by 4 on success. If there are fewer
+    /// than 4 remaining bytes, an error is returned and the position is left
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, TryGetError};
+    ///
+    /// let mut buf = &b"\x08\x09\xA0\xA1 hello"[..];
+    /// assert_eq!(Ok(0x0809A0A1), buf.try_get_i32());
+    ///
+    /// let mut buf = &b"\x08"[..];
+    /// assert_eq!(Err(TryGetError { requested: 4, available: 1 }), buf.try_get_i32());
+    /// assert_eq!(1, buf.remaining());
+    /// ```
+    fn try_get_i32(&mut self) -> Result<i32, TryGetError> {
+        buf_try_get_impl!(self, 4, get_i32)
+    }
+
+    /// Gets a signed 32 bit integer from `self` in little-endian byte order.
+    ///
+    /// The current position is advanced by 4 on success. If there are fewer
+    /// than 4 remaining bytes, an error is returned and the position is left
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, TryGetError};
+    ///
+    /// let mut buf = &b"\xA1\xA0\x09\x08 hello"[..];
+    /// assert_eq!(Ok(0x0809A0A1), buf.try_get_i32_le());
+    ///
+    /// let mut buf = &b"\x08"[..];
+    /// assert_eq!(Err(TryGetError { requested: 4, available: 1 }), buf.try_get_i32_le());
+    /// assert_eq!(1, buf.remaining());
+    /// ```
+    fn try_get_i32_le(&mut self) -> Result<i32, TryGetError> {
+        buf_try_get_impl!(self, 4, get_i32_le)
+    }
+
+    /// Gets an unsigned 64 bit integer from `self` in big-endian byte order.
+    ///
+    /// The current position is advanced by 8 on success. If there are fewer
+    /// than 8 remaining bytes, an error is returned and the position is left
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, TryGetError};
+    ///
+    /// let mut buf = &b"\x01\x02\x03\x04\x05\x06\x07\x08 hello"[..];
+    /// assert_eq!(Ok(0x0102030405060708), buf.try_get_u64());
+    ///
+    /// let mut buf = &b"\x08"[..];
+    /// assert_eq!(Err(TryGetError { requested: 8, available: 1 }), buf.try_get_u64());
+    /// assert_eq!(1, buf.remaining());
+    /// ```
+    fn try_get_u64(&mut self) -> Result<u64, TryGetError> {
+        buf_try_get_impl!(self, 8, get_u64)
+    }
+
+    /// Gets an unsigned 64 bit integer from `self` in little-endian byte order.
+    ///
+    /// The current position is advanced by 8 on success. If there are fewer
+    /// than 8 remaining bytes, an error is returned and the position is left
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, TryGetError};
+    ///
+    /// let mut buf = &b"\x08\x07\x06\x05\x04\x03\x02\x01 hello"[..];
+    /// assert_eq!(Ok(0x0102030405060708), buf.try_get_u64_le());
+    ///
+    /// let mut buf = &b"\x08"[..];
+    /// assert_eq!(Err(TryGetError { requested: 8, available: 1 }), buf.try_get_u64_le());
+    /// assert_eq!(1, buf.remaining());
+    /// ```
+    fn try_get_u64_le(&mut self) -> Result<u64, TryGetError> {
+        buf_try_get_impl!(self, 8, get_u64_le)
+    }
+
+    /// Gets a signed 64 bit integer from `self` in big-endian byte order.
+    ///
+    /// The current position is advanced by 8 on success. If there are fewer
+    /// than 8 remaining bytes, an error is returned and the position is left
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, TryGetError};
+    ///
+    /// let mut buf = &b"\x01\x02\x03\x04\x05\x06\x07\x08 hello"[..];
+    /// assert_eq!(Ok(0x0102030405060708), buf.try_get_i64());
+    ///
+    /// let mut buf = &b"\x08"[..];
+    /// assert_eq!(Err(TryGetError { requested: 8, available: 1 }), buf.try_get_i64());
+    /// assert_eq!(1, buf.remaining());
+    /// ```
+    fn try_get_i64(&mut self) -> Result<i64, TryGetError> {
+        buf_try_get_impl!(self, 8, get_i64)
+    }
+
+    /// Gets a signed 64 bit integer from `self` in little-endian byte order.
+    ///
+    /// The current position is advanced by 8 on success. If there are fewer
+    /// than 8 remaining bytes, an error is returned and the position is left
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, TryGetError};
+    ///
+    /// let mut buf = &b"\x08\x07\x06\x05\x04\x03\x02\x01 hello"[..];
+    /// assert_eq!(Ok(0x0102030405060708), buf.try_get_i64_le());
+    ///
+    /// let mut buf = &b"\x08"[..];
+    /// assert_eq!(Err(TryGetError { requested: 8, available: 1 }), buf.try_get_i64_le());
+    /// assert_eq!(1, buf.remaining());
+    /// ```
+    fn try_get_i64_le(&mut self) -> Result<i64, TryGetError> {
+        buf_try_get_impl!(self, 8, get_i64_le)
+    }
+
+    /// Gets an unsigned 128 bit integer from `self` in big-endian byte order.
+    ///
+    /// The current position is advanced by 16 on success. If there are fewer
+    /// than 16 remaining bytes, an error is returned and the position is left
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, TryGetError};
+    ///
+    /// let mut buf = &b"\x01\x02\x03\x04\x05\x06\x07\x08\x09\x10\x11\x12\x13\x14\x15\x16 hello"[..];
+    /// assert_eq!(Ok(0x01020304050607080910111213141516), buf.try_get_u128());
+    ///
+    /// let mut buf = &b"\x08"[..];
+    /// assert_eq!(Err(TryGetError { requested: 16, available: 1 }), buf.try_get_u128());
+    /// assert_eq!(1, buf.remaining());
+    /// ```
+    fn try_get_u128(&mut self) -> Result<u128, TryGetError> {
+        buf_try_get_impl!(self, 16, get_u128)
+    }
+
+    /// Gets an unsigned 128 bit integer from `self` in little-endian byte order.
+    ///
+    /// The current position is advanced by 16 on success. If there are fewer
+    /// than 16 remaining bytes, an error is returned and the position is left
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, TryGetError};
+    ///
+    /// let mut buf = &b"\x16\x15\x14\x13\x12\x11\x10\x09\x08\x07\x06\x05\x04\x03\x02\x01 hello"[..];
+    /// assert_eq!(Ok(0x01020304050607080910111213141516), buf.try_get_u128_le());
+    ///
+    /// let mut buf = &b"\x08"[..];
+    /// assert_eq!(Err(TryGetError { requested: 16, available: 1 }), buf.try_get_u128_le());
+    /// assert_eq!(1, buf.remaining());
+    /// ```
+    fn try_get_u128_le(&mut self) -> Result<u128, TryGetError> {
+        buf_try_get_impl!(self, 16, get_u128_le)
+    }
+
+    /// Gets a signed 128 bit integer from `self` in big-endian byte order.
+    ///
+    /// The current position is advanced by 16 on success. If there are fewer
+    /// than 16 remaining bytes, an error is returned and the position is left
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, TryGetError};
+    ///
+    /// let mut buf = &b"\x01\x02\x03\x04\x05\x06\x07\x08\x09\x10\x11\x12\x13\x14\x15\x16 hello"[..];
+    /// assert_eq!(Ok(0x01020304050607080910111213141516), buf.try_get_i128());
+    ///
+    /// let mut buf = &b"\x08"[..];
+    /// assert_eq!(Err(TryGetError { requested: 16, available: 1 }), buf.try_get_i128());
+    /// assert_eq!(1, buf.remaining());
+    /// ```
+    fn try_get_i128(&mut self) -> Result<i128, TryGetError> {
+        buf_try_get_impl!(self, 16, get_i128)
+    }
+
+    /// Gets a signed 128 bit integer from `self` in little-endian byte order.
+    ///
+    /// The current position is advanced by 16 on success. If there are fewer
+    /// than 16 remaining bytes, an error is returned and the position is left
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, TryGetError};
+    ///
+    /// let mut buf = &b"\x16\x15\x14\x13\x12\x11\x10\x09\x08\x07\x06\x05\x04\x03\x02\x01 hello"[..];
+    /// assert_eq!(Ok(0x01020304050607080910111213141516), buf.try_get_i128_le());
+    ///
+    /// let mut buf = &b"\x08"[..];
+    /// assert_eq!(Err(TryGetError { requested: 16, available: 1 }), buf.try_get_i128_le());
+    /// assert_eq!(1, buf.remaining());
+    /// ```
+    fn try_get_i128_le(&mut self) -> Result<i128, TryGetError> {
+        buf_try_get_impl!(self, 16, get_i128_le)
+    }
+
+    /// Gets an unsigned n-byte integer from `self` in big-endian byte order.
+    ///
+    /// The current position is advanced by `nbytes` on success. If there
+    /// are fewer than `nbytes` remaining bytes, an error is returned and the
+    /// position is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, TryGetError};
+    ///
+    /// let mut buf = &b"\x01\x02\x03 hello"[..];
+    /// assert_eq!(Ok(0x010203), buf.try_get_uint(3));
+    ///
+    /// let mut buf = &b"\x01\x02"[..];
+    /// assert_eq!(Err(TryGetError { requested: 3, available: 2 }), buf.try_get_uint(3));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `nbytes` is greater than 8.
+    fn try_get_uint(&mut self, nbytes: usize) -> Result<u64, TryGetError> {
+        buf_try_get_impl!(self, nbytes, get_uint, nbytes)
+    }
+
+    /// Gets an unsigned n-byte integer from `self` in little-endian byte order.
+    ///
+    /// The current position is advanced by `nbytes` on success. If there
+    /// are fewer than `nbytes` remaining bytes, an error is returned and the
+    /// position is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, TryGetError};
+    ///
+    /// let mut buf = &b"\x03\x02\x01 hello"[..];
+    /// assert_eq!(Ok(0x010203), buf.try_get_uint_le(3));
+    ///
+    /// let mut buf = &b"\x01\x02"[..];
+    /// assert_eq!(Err(TryGetError { requested: 3, available: 2 }), buf.try_get_uint_le(3));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `nbytes` is greater than 8.
+    fn try_get_uint_le(&mut self, nbytes: usize) -> Result<u64, TryGetError> {
+        buf_try_get_impl!(self, nbytes, get_uint_le, nbytes)
+    }
+
+    /// Gets a signed n-byte integer from `self` in big-endian byte order.
+    ///
+    /// The current position is advanced by `nbytes` on success. If there
+    /// are fewer than `nbytes` remaining bytes, an error is returned and the
+    /// position is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, TryGetError};
+    ///
+    /// let mut buf = &b"\x01\x02\x03 hello"[..];
+    /// assert_eq!(Ok(0x010203), buf.try_get_int(3));
+    ///
+    /// let mut buf = &b"\x01\x02"[..];
+    /// assert_eq!(Err(TryGetError { requested: 3, available: 2 }), buf.try_get_int(3));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `nbytes` is greater than 8.
+    fn try_get_int(&mut self, nbytes: usize) -> Result<i64, TryGetError> {
+        buf_try_get_impl!(self, nbytes, get_int, nbytes)
+    }
+
+    /// Gets a signed n-byte integer from `self` in little-endian byte order.
+    ///
+    /// The current position is advanced by `nbytes` on success. If there
+    /// are fewer than `nbytes` remaining bytes, an error is returned and the
+    /// position is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, TryGetError};
+    ///
+    /// let mut buf = &b"\x03\x02\x01 hello"[..];
+    /// assert_eq!(Ok(0x010203), buf.try_get_int_le(3));
+    ///
+    /// let mut buf = &b"\x01\x02"[..];
+    /// assert_eq!(Err(TryGetError { requested: 3, available: 2 }), buf.try_get_int_le(3));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `nbytes` is greater than 8.
+    fn try_get_int_le(&mut self, nbytes: usize) -> Result<i64, TryGetError> {
+        buf_try_get_impl!(self, nbytes, get_int_le, nbytes)
+    }
+
+    /// Gets an IEEE754 single-precision (4 bytes) floating point number from
+    /// `self` in big-endian byte order.
+    ///
+    /// The current position is advanced by 4 on success. If there are fewer
+    /// than 4 remaining bytes, an error is returned and the position is left
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, TryGetError};
+    ///
+    /// let mut buf = &b"\x3F\x99\x99\x9A hello"[..];
+    /// assert_eq!(Ok(1.2f32), buf.try_get_f32());
+    ///
+    /// let mut buf = &b"\x08"[..];
+    /// assert_eq!(Err(TryGetError { requested: 4, available: 1 }), buf.try_get_f32());
+    /// assert_eq!(1, buf.remaining());
+    /// ```
+    fn try_get_f32(&mut self) -> Result<f32, TryGetError> {
+        buf_try_get_impl!(self, 4, get_f32)
+    }
+
+    /// Gets an IEEE754 single-precision (4 bytes) floating point number from
+    /// `self` in little-endian byte order.
+    ///
+    /// The current position is advanced by 4 on success. If there are fewer
+    /// than 4 remaining bytes, an error is returned and the position is left
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, TryGetError};
+    ///
+    /// let mut buf = &b"\x9A\x99\x99\x3F hello"[..];
+    /// assert_eq!(Ok(1.2f32), buf.try_get_f32_le());
+    ///
+    /// let mut buf = &b"\x08"[..];
+    /// assert_eq!(Err(TryGetError { requested: 4, available: 1 }), buf.try_get_f32_le());
+    /// assert_eq!(1, buf.remaining());
+    /// ```
+    fn try_get_f32_le(&mut self) -> Result<f32, TryGetError> {
+        buf_try_get_impl!(self, 4, get_f32_le)
+    }
+
+    /// Gets an IEEE754 double-precision (8 bytes) floating point number from
+    /// `self` in big-endian byte order.
+    ///
+    /// The current position is advanced by 8 on success. If there are fewer
+    /// than 8 remaining bytes, an error is returned and the position is left
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, TryGetError};
+    ///
+    /// let mut buf = &b"\x3F\xF3\x33\x33\x33\x33\x33\x33 hello"[..];
+    /// assert_eq!(Ok(1.2f64), buf.try_get_f64());
+    ///
+    /// let mut buf = &b"\x08"[..];
+    /// assert_eq!(Err(TryGetError { requested: 8, available: 1 }), buf.try_get_f64());
+    /// assert_eq!(1, buf.remaining());
+    /// ```
+    fn try_get_f64(&mut self) -> Result<f64, TryGetError> {
+        buf_try_get_impl!(self, 8, get_f64)
+    }
+
+    /// Gets an IEEE754 double-precision (8 bytes) floating point number from
+    /// `self` in little-endian byte order.
+    ///
+    /// The current position is advanced by 8 on success. If there are fewer
+    /// than 8 remaining bytes, an error is returned and the position is left
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, TryGetError};
+    ///
+    /// let mut buf = &b"\x33\x33\x33\x33\x33\x33\xF3\x3F hello"[..];
+    /// assert_eq!(Ok(1.2f64), buf.try_get_f64_le());
+    ///
+    /// let mut buf = &b"\x08"[..];
+    /// assert_eq!(Err(TryGetError { requested: 8, available: 1 }), buf.try_get_f64_le());
+    /// assert_eq!(1, buf.remaining());
+    /// ```
+    fn try_get_f64_le(&mut self) -> Result<f64, TryGetError> {
+        buf_try_get_impl!(self, 8, get_f64_le)
+    }
+
+    /// Gets a fixed-size array of `N` bytes from `self`.
+    ///
+    /// The current position is advanced by `N` on success. If there are fewer
+    /// than `N` remaining bytes, an error is returned and the position is left
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, TryGetError};
+    ///
+    /// let mut buf = &b"\x01\x02\x03\x04 hello"[..];
+    /// assert_eq!(Ok([1, 2, 3, 4]), buf.try_get_array());
+    ///
+    /// let mut buf = &b"\x01\x02"[..];
+    /// assert_eq!(Err(TryGetError { requested: 4, available: 2 }), buf.try_get_array::<4>());
+    /// assert_eq!(2, buf.remaining());
+    /// ```
+    fn try_get_array<const N: usize>(&mut self) -> Result<[u8; N], TryGetError> where Self: Sized {
+        buf_try_get_impl!(self, N, get_array)
+    }
+
+    /// Transforms a `Buf` into a concrete buffer.
+    ///
+    /// `collect()` can operate on any value that implements `Buf`, and turn it
+    /// into the relevant concrete buffer type.
+    ///
+    /// # Examples
+    ///
+    /// Collecting a buffer and loading the contents into a `Vec<u8>`.
+    ///
+    /// ```
+    /// use bytes::Buf;
+    ///
+    /// let buf = &b"hello world"[..];
+    /// let vec: Vec<u8> = buf.collect();
+    ///
+    /// assert_eq!(vec, b"hello world");
+    /// ```
+    fn collect<B>(self) -> B
+        where Self: Sized,
+              B: FromBuf,
+    {
+        B::from_buf(self)
+    }
+
+    /// Creates an adaptor which will read at most `limit` bytes from `self`.
+    ///
+    /// This function returns a new instance of `Buf` which will read at most
+    /// `limit` bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, BufMut};
+    ///
+    /// let mut buf = b"hello world"[..].take(5);
+    /// let mut dst = vec![];
+    ///
+    /// dst.put(&mut buf);
+    /// assert_eq!(dst, b"hello");
+    ///
+    /// let mut buf = buf.into_inner();
+    /// dst.clear();
+    /// dst.put(&mut buf);
+    /// assert_eq!(dst, b" world");
+    /// ```
+    fn take(self, limit: usize) -> Take<Self>
+        where Self: Sized
+    {
+        super::take::new(self, limit)
+    }
+
+    /// Creates an adaptor which will chain this buffer with another.
+    ///
+    /// The returned `Buf` instance will first consume all bytes from `self`.
+    /// Afterwards the output is equivalent to the output of next.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Buf;
+    ///
+    /// let chain = b"hello "[..].chain(&b"world"[..]);
+    ///
+    /// let full: Vec<u8> = chain.collect();
+    /// assert_eq!(full, b"hello world");
+    /// ```
+    fn chain<U>(self, next: U) -> Chain<Self, U::Buf>
+        where U: IntoBuf,
+              Self: Sized,
+    {
+        Chain::new(self, next.into_buf())
+    }
+
+    /// Creates a "by reference" adaptor for this instance of `Buf`.
+    ///
+    /// The returned adaptor also implements `Buf` and will simply borrow `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, BufMut};
+    ///
+    /// let mut buf = &b"hello world"[..];
+    /// let mut dst = vec![];
+    ///
+    /// {
+    ///     let mut reference = buf.by_ref();
+    ///     dst.put(&mut reference.take(5));
+    ///     assert_eq!(dst, &b"hello"[..]);
+    /// } // drop our &mut reference so we can use `buf` again
+    ///
+    /// dst.clear();
+    /// dst.put(&mut buf);
+    /// assert_eq!(dst, &b" world"[..]);
+    /// ```
+    fn by_ref(&mut self) -> &mut Self where Self: Sized {
+        self
+    }
+
+    /// Creates an adaptor which implements the `Read` trait for `self`.
+    ///
+    /// This function returns a new value which implements `Read` by adapting
+    /// the `Read` trait functions to the `Buf` trait functions. Given that
+    /// `Buf` operations are infallible, none of the `Read` functions will
+    /// return with `Err`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, IntoBuf, Bytes};
+    /// use std::io::Read;
+    ///
+    /// let buf = Bytes::from("hello world").into_buf();
+    ///
+    /// let mut reader = buf.reader();
+    /// let mut dst = [0; 1024];
+    ///
+    /// let num = reader.read(&mut dst).unwrap();
+    ///
+    /// assert_eq!(11, num);
+    /// assert_eq!(&dst[..11], &b"hello world"[..]);
+    /// ```
+    #[cfg(feature = "std")]
+    fn reader(self) -> Reader<Self> where Self: Sized {
+        super::reader::new(self)
+    }
+}
+
+impl<T: Buf + ?Sized> Buf for &mut T {
+    fn remaining(&self) -> usize {
+        (**self).remaining()
+    }
+
     fn bytes(&self) -> &[u8] {
-        self.as_ref().map(AsRef::as_ref)
-            .unwrap_or(Default::default())
+        (**self).bytes()
+    }
+
+    fn bytes_at(&self, offset: usize) -> &[u8] {
+        (**self).bytes_at(offset)
+    }
+
+    #[cfg(feature = "std")]
+    fn bytes_vectored<'b>(&'b self, dst: &mut [IoSlice<'b>]) -> usize {
+        (**self).bytes_vectored(dst)
     }
 
     fn advance(&mut self, cnt: usize) {
-        if cnt == 0 {
-            return;
-        }
+        (**self).advance(cnt)
+    }
 
-        if self.is_none() {
-            panic!("overflow");
-        } else {
-            assert_eq!(1, cnt);
-            *self = None;
+    fn peek_slice(&self, dst: &mut [u8]) {
+        (**self).peek_slice(dst)
+    }
+
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        (**self).copy_to_bytes(len)
+    }
+}
+
+impl<T: Buf + ?Sized> Buf for Box<T> {
+    fn remaining(&self) -> usize {
+        (**self).remaining()
+    }
+
+    fn bytes(&self) -> &[u8] {
+        (**self).bytes()
+    }
+
+    fn bytes_at(&self, offset: usize) -> &[u8] {
+        (**self).bytes_at(offset)
+    }
+
+    #[cfg(feature = "std")]
+    fn bytes_vectored<'b>(&'b self, dst: &mut [IoSlice<'b>]) -> usize {
+        (**self).bytes_vectored(dst)
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        (**self).advance(cnt)
+    }
+
+    fn peek_slice(&self, dst: &mut [u8]) {
+        (**self).peek_slice(dst)
+    }
+
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        (**self).copy_to_bytes(len)
+    }
+}
+
+impl Buf for &[u8] {
+    #[inline]
+    fn remaining(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn bytes(&self) -> &[u8] {
+        self
+    }
+
+    #[inline]
+    fn advance(&mut self, cnt: usize) {
+        *self = &self[cnt..];
+    }
+}
+
+// Copies the start of `buf` into `dst` using the slices returned by
+// `bytes_vectored`. Returns `false` if they do not cover `dst`.
+#[cfg(feature = "std")]
+fn peek_vectored<B: Buf + ?Sized>(buf: &B, dst: &mut [u8]) -> bool {
+    let mut slices = [IoSlice::new(&[]); 64];
+    let n = buf.bytes_vectored(&mut slices);
+    let mut off = 0;
+
+    for src in &slices[..n] {
+        if off == dst.len() {
+            break;
         }
+
+        let cnt = cmp::min(src.len(), dst.len() - off);
+        dst[off..off + cnt].copy_from_slice(&src[..cnt]);
+        off += cnt;
     }
+
+    off == dst.len()
+}
+
+#[cfg(not(feature = "std"))]
+fn peek_vectored<B: Buf + ?Sized>(_: &B, _: &mut [u8]) -> bool {
+    false
 }
 
 // The existence of this function makes the compiler catch if the Buf
 // trait is "object-safe" or not.
 fn _assert_trait_object(_b: &dyn Buf) {}
+
+/// The error returned by the fallible `Buf::try_get_*` methods when `self`
+/// does not have enough remaining bytes.
+///
+/// # Examples
+///
+/// ```
+/// use bytes::{Buf, TryGetError};
+///
+/// let mut buf = &b"\x00\x01"[..];
+/// let err = buf.try_get_u32().unwrap_err();
+///
+/// assert_eq!(err, TryGetError { requested: 4, available: 2 });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TryGetError {
+    /// The number of bytes needed to complete the read.
+    pub requested: usize,
+
+    /// The number of bytes remaining in the buffer.
+    pub available: usize,
+}
+
+impl fmt::Display for TryGetError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            fmt,
+            "not enough bytes remaining in buffer to read value (requested {} but only {} available)",
+            self.requested,
+            self.available
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TryGetError {}
@@ -0,0 +1,298 @@
+use crate::{Buf, BufMut, Bytes, BytesMut};
+
+use core::cmp;
+use core::iter::FromIterator;
+
+use alloc::collections::VecDeque;
+#[cfg(feature = "std")]
+use std::io::IoSlice;
+
+/// A sequence of `Bytes` segments viewed as one continuous buffer.
+///
+/// Unlike [`Chain`], which links exactly two buffers, `BufList` holds any
+/// number of segments, which makes it convenient to assemble a message out of
+/// a dynamic number of pieces without copying them. Reading from a `BufList`
+/// through the [`Buf`] trait consumes the segments in order, and
+/// [`bytes_vectored`] exposes all of them at once. [`copy_to_bytes`] does not
+/// copy when the requested bytes lie in the first segment.
+///
+/// Empty segments are never stored.
+///
+/// [`Chain`]: struct.Chain.html
+/// [`Buf`]: trait.Buf.html
+/// [`bytes_vectored`]: trait.Buf.html#method.bytes_vectored
+/// [`copy_to_bytes`]: trait.Buf.html#method.copy_to_bytes
+///
+/// # Examples
+///
+/// ```
+/// use bytes::{Buf, Bytes};
+/// use bytes::buf::BufList;
+///
+/// let mut list = BufList::new();
+/// list.push_back(Bytes::from_static(b"hello "));
+/// list.push_back(Bytes::from_static(b"world"));
+///
+/// assert_eq!(list.remaining(), 11);
+/// assert_eq!(list.bytes(), b"hello ");
+///
+/// let full: Bytes = list.collect();
+/// assert_eq!(full[..], b"hello world"[..]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BufList {
+    bufs: VecDeque<Bytes>,
+    remaining: usize,
+}
+
+impl BufList {
+    /// Creates a new, empty `BufList`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Buf;
+    /// use bytes::buf::BufList;
+    ///
+    /// let list = BufList::new();
+    /// assert!(!list.has_remaining());
+    /// ```
+    pub fn new() -> BufList {
+        BufList::default()
+    }
+
+    /// Returns the number of segments in the list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use bytes::buf::BufList;
+    ///
+    /// let list: BufList = vec![
+    ///     Bytes::from_static(b"hello"),
+    ///     Bytes::new(),
+    ///     Bytes::from_static(b"world"),
+    /// ].into_iter().collect();
+    ///
+    /// assert_eq!(list.num_segments(), 2);
+    /// ```
+    pub fn num_segments(&self) -> usize {
+        self.bufs.len()
+    }
+
+    /// Appends a segment to the back of the list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, Bytes};
+    /// use bytes::buf::BufList;
+    ///
+    /// let mut list = BufList::new();
+    /// list.push_back(Bytes::from_static(b"hello"));
+    /// list.push_back(Bytes::from_static(b"world"));
+    ///
+    /// assert_eq!(list.bytes(), b"hello");
+    /// ```
+    pub fn push_back(&mut self, buf: Bytes) {
+        if buf.is_empty() {
+            return;
+        }
+
+        self.remaining += buf.len();
+        self.bufs.push_back(buf);
+    }
+
+    /// Prepends a segment to the front of the list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, Bytes};
+    /// use bytes::buf::BufList;
+    ///
+    /// let mut list = BufList::new();
+    /// list.push_back(Bytes::from_static(b"world"));
+    /// list.push_front(Bytes::from_static(b"hello"));
+    ///
+    /// assert_eq!(list.bytes(), b"hello");
+    /// ```
+    pub fn push_front(&mut self, buf: Bytes) {
+        if buf.is_empty() {
+            return;
+        }
+
+        self.remaining += buf.len();
+        self.bufs.push_front(buf);
+    }
+
+    /// Splits the list into two at the given index.
+    ///
+    /// Afterwards `self` contains elements `[at, remaining)`, and the returned
+    /// `BufList` contains elements `[0, at)`.
+    ///
+    /// No data is copied. At most one segment is split in two, which is an
+    /// O(1) operation that just increases the reference count.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, Bytes};
+    /// use bytes::buf::BufList;
+    ///
+    /// let mut list = BufList::new();
+    /// list.push_back(Bytes::from_static(b"hello "));
+    /// list.push_back(Bytes::from_static(b"world"));
+    ///
+    /// let head = list.split_to(8);
+    ///
+    /// assert_eq!(head.num_segments(), 2);
+    /// assert_eq!(head.remaining(), 8);
+    /// assert_eq!(list.bytes(), b"rld");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > remaining`.
+    pub fn split_to(&mut self, at: usize) -> BufList {
+        assert!(at <= self.remaining, "split_to out of bounds");
+
+        let mut head = BufList::new();
+        let mut left = at;
+
+        while left > 0 {
+            let front = self.bufs.front_mut().unwrap();
+
+            if front.len() > left {
+                head.push_back(front.split_to(left));
+                break;
+            }
+
+            left -= front.len();
+
+            let buf = self.bufs.pop_front().unwrap();
+            head.push_back(buf);
+        }
+
+        self.remaining -= at;
+        head
+    }
+}
+
+impl Buf for BufList {
+    fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    fn bytes(&self) -> &[u8] {
+        match self.bufs.front() {
+            Some(buf) => &buf[..],
+            None => &[],
+        }
+    }
+
+    fn bytes_at(&self, mut offset: usize) -> &[u8] {
+        for buf in &self.bufs {
+            if offset < buf.len() {
+                return &buf[offset..];
+            }
+
+            offset -= buf.len();
+        }
+
+        &[]
+    }
+
+    fn advance(&mut self, mut cnt: usize) {
+        assert!(cnt <= self.remaining, "cannot advance past `remaining`");
+
+        self.remaining -= cnt;
+
+        while cnt > 0 {
+            let front = self.bufs.front_mut().unwrap();
+
+            if front.len() > cnt {
+                front.advance(cnt);
+                return;
+            }
+
+            cnt -= front.len();
+            self.bufs.pop_front();
+        }
+    }
+
+    fn peek_slice(&self, dst: &mut [u8]) {
+        assert!(self.remaining >= dst.len(), "cannot peek past `remaining`");
+
+        let mut off = 0;
+
+        for buf in &self.bufs {
+            if off == dst.len() {
+                break;
+            }
+
+            let cnt = cmp::min(buf.len(), dst.len() - off);
+            dst[off..off + cnt].copy_from_slice(&buf[..cnt]);
+            off += cnt;
+        }
+    }
+
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        assert!(len <= self.remaining, "`len` greater than remaining");
+
+        match self.bufs.front_mut() {
+            Some(front) if front.len() >= len => {
+                let ret = front.split_to(len);
+
+                if front.is_empty() {
+                    self.bufs.pop_front();
+                }
+
+                self.remaining -= len;
+                ret
+            }
+            _ => {
+                let mut ret = BytesMut::with_capacity(len);
+                ret.put(self.by_ref().take(len));
+                ret.freeze()
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn bytes_vectored<'a>(&'a self, dst: &mut [IoSlice<'a>]) -> usize {
+        let mut n = 0;
+
+        for (buf, slot) in self.bufs.iter().zip(dst.iter_mut()) {
+            *slot = IoSlice::new(&buf[..]);
+            n += 1;
+        }
+
+        n
+    }
+}
+
+impl Extend<Bytes> for BufList {
+    fn extend<T>(&mut self, iter: T) where T: IntoIterator<Item = Bytes> {
+        for buf in iter {
+            self.push_back(buf);
+        }
+    }
+}
+
+impl FromIterator<Bytes> for BufList {
+    fn from_iter<T: IntoIterator<Item = Bytes>>(iter: T) -> BufList {
+        let mut list = BufList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl From<Bytes> for BufList {
+    fn from(buf: Bytes) -> BufList {
+        let mut list = BufList::new();
+        list.push_back(buf);
+        list
+    }
+}
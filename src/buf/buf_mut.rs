@@ -1,6 +1,12 @@
-use super::{IntoBuf, Writer};
+use super::{IntoBuf, LenPrefix, UninitSlice};
+use crate::encoding::{Base64, Hex};
+use super::varint;
+#[cfg(feature = "std")]
+use super::Writer;
 
-use std::{mem, cmp, io::IoSliceMut, ptr, usize};
+use core::{mem, cmp, usize};
+
+use alloc::{boxed::Box, vec::Vec};
 
 /// A trait for values that provide sequential write access to bytes.
 ///
@@ -27,7 +33,7 @@ pub trait BufMut {
     /// position until the end of the buffer is reached.
     ///
     /// This value is greater than or equal to the length of the slice returned
-    /// by `bytes_mut`.
+    /// by `chunk_mut`.
     ///
     /// # Examples
     ///
@@ -52,7 +58,7 @@ pub trait BufMut {
 
     /// Advance the internal cursor of the BufMut
     ///
-    /// The next call to `bytes_mut` will return a slice starting `cnt` bytes
+    /// The next call to `chunk_mut` will return a slice starting `cnt` bytes
     /// further into the underlying buffer.
     ///
     /// This function is unsafe because there is no guarantee that the bytes
@@ -65,17 +71,14 @@ pub trait BufMut {
     ///
     /// let mut buf = Vec::with_capacity(16);
     ///
-    /// unsafe {
-    ///     buf.bytes_mut()[0] = b'h';
-    ///     buf.bytes_mut()[1] = b'e';
-    ///
-    ///     buf.advance_mut(2);
+    /// // Write some data
+    /// buf.chunk_mut()[0..2].copy_from_slice(b"he");
+    /// unsafe { buf.advance_mut(2) };
     ///
-    ///     buf.bytes_mut()[0] = b'l';
-    ///     buf.bytes_mut()[1..3].copy_from_slice(b"lo");
+    /// // write more bytes
+    /// buf.chunk_mut()[0..3].copy_from_slice(b"llo");
     ///
-    ///     buf.advance_mut(3);
-    /// }
+    /// unsafe { buf.advance_mut(3); }
     ///
     /// assert_eq!(5, buf.len());
     /// assert_eq!(buf, b"hello");
@@ -117,13 +120,17 @@ pub trait BufMut {
     }
 
     /// Returns a mutable slice starting at the current BufMut position and of
-    /// length between 0 and `BufMut::remaining_mut()`. Note that this *can* be shorter than the
-    /// whole remainder of the buffer (this allows non-continuous implementation).
+    /// length between 0 and `BufMut::remaining_mut()`. Note that this *can* be
+    /// shorter than the whole remainder of the buffer (this allows
+    /// non-continuous implementation).
     ///
     /// This is a lower level function. Most operations are done with other
     /// functions.
     ///
-    /// The returned byte slice may represent uninitialized memory.
+    /// The returned byte slice may represent uninitialized memory, which is
+    /// why it is wrapped in an [`UninitSlice`] that only allows writing to it.
+    ///
+    /// [`UninitSlice`]: buf/struct.UninitSlice.html
     ///
     /// # Examples
     ///
@@ -132,17 +139,15 @@ pub trait BufMut {
     ///
     /// let mut buf = Vec::with_capacity(16);
     ///
-    /// unsafe {
-    ///     buf.bytes_mut()[0] = b'h';
-    ///     buf.bytes_mut()[1] = b'e';
+    /// buf.chunk_mut().write_byte(0, b'h');
+    /// buf.chunk_mut().write_byte(1, b'e');
     ///
-    ///     buf.advance_mut(2);
+    /// unsafe { buf.advance_mut(2) };
     ///
-    ///     buf.bytes_mut()[0] = b'l';
-    ///     buf.bytes_mut()[1..3].copy_from_slice(b"lo");
+    /// buf.chunk_mut().write_byte(0, b'l');
+    /// buf.chunk_mut()[1..3].copy_from_slice(b"lo");
     ///
-    ///     buf.advance_mut(3);
-    /// }
+    /// unsafe { buf.advance_mut(3) };
     ///
     /// assert_eq!(5, buf.len());
     /// assert_eq!(buf, b"hello");
@@ -150,48 +155,65 @@ pub trait BufMut {
     ///
     /// # Implementer notes
     ///
-    /// This function should never panic. `bytes_mut` should return an empty
+    /// This function should never panic. `chunk_mut` should return an empty
     /// slice **if and only if** `remaining_mut` returns 0. In other words,
-    /// `bytes_mut` returning an empty slice implies that `remaining_mut` will
-    /// return 0 and `remaining_mut` returning 0 implies that `bytes_mut` will
+    /// `chunk_mut` returning an empty slice implies that `remaining_mut` will
+    /// return 0 and `remaining_mut` returning 0 implies that `chunk_mut` will
     /// return an empty slice.
-    unsafe fn bytes_mut(&mut self) -> &mut [u8];
+    fn chunk_mut(&mut self) -> &mut UninitSlice;
 
-    /// Fills `dst` with potentially multiple mutable slices starting at `self`'s
-    /// current position.
+    /// Fills `dst` with potentially multiple mutable slices starting at
+    /// `self`'s current position.
     ///
-    /// If the `BufMut` is backed by disjoint slices of bytes, `bytes_vectored_mut`
-    /// enables fetching more than one slice at once. `dst` is a slice of
-    /// mutable `IoSliceMut` references, enabling the slice to be directly used with
-    /// [`readv`] without any further conversion. The sum of the lengths of all
-    /// the buffers in `dst` will be less than or equal to
-    /// `Buf::remaining_mut()`.
+    /// If the `BufMut` is backed by disjoint slices of bytes,
+    /// `chunks_vectored_mut` enables fetching more than one slice at once. The
+    /// sum of the lengths of all the slices in `dst` will be less than or
+    /// equal to `BufMut::remaining_mut()`.
     ///
     /// The entries in `dst` will be overwritten, but the data **contained** by
-    /// the slices **will not** be modified. If `bytes_vectored_mut` does not fill every
-    /// entry in `dst`, then `dst` is guaranteed to contain all remaining slices
-    /// in `self.
+    /// the slices **will not** be modified. If `chunks_vectored_mut` does not
+    /// fill every entry in `dst`, then `dst` is guaranteed to contain all
+    /// remaining slices in `self`.
     ///
     /// This is a lower level function. Most operations are done with other
     /// functions.
     ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::BufMut;
+    /// use bytes::buf::{Chain, UninitSlice};
+    ///
+    /// let mut a = [0; 3];
+    /// let mut b = [0; 3];
+    /// let mut buf = Chain::new(&mut a[..], &mut b[..]);
+    ///
+    /// let mut dst: [&mut UninitSlice; 2] = Default::default();
+    /// assert_eq!(2, buf.chunks_vectored_mut(&mut dst));
+    ///
+    /// dst[0].copy_from_slice(b"foo");
+    /// dst[1].copy_from_slice(b"bar");
+    /// unsafe { buf.advance_mut(6) };
+    ///
+    /// assert_eq!(b"foo", &a);
+    /// assert_eq!(b"bar", &b);
+    /// ```
+    ///
     /// # Implementer notes
     ///
     /// This function should never panic. Once the end of the buffer is reached,
-    /// i.e., `BufMut::remaining_mut` returns 0, calls to `bytes_vectored_mut` must
-    /// return 0 without mutating `dst`.
+    /// i.e., `BufMut::remaining_mut` returns 0, calls to `chunks_vectored_mut`
+    /// must return 0 without mutating `dst`.
     ///
     /// Implementations should also take care to properly handle being called
     /// with `dst` being a zero length slice.
-    ///
-    /// [`readv`]: http://man7.org/linux/man-pages/man2/readv.2.html
-    unsafe fn bytes_vectored_mut<'a>(&'a mut self, dst: &mut [IoSliceMut<'a>]) -> usize {
+    fn chunks_vectored_mut<'a>(&'a mut self, dst: &mut [&'a mut UninitSlice]) -> usize {
         if dst.is_empty() {
             return 0;
         }
 
         if self.has_remaining_mut() {
-            dst[0] = IoSliceMut::new(self.bytes_mut());
+            dst[0] = self.chunk_mut();
             1
         } else {
             0
@@ -228,15 +250,12 @@ pub trait BufMut {
         while src.has_remaining() {
             let l;
 
-            unsafe {
+            {
                 let s = src.bytes();
-                let d = self.bytes_mut();
+                let d = self.chunk_mut();
                 l = cmp::min(s.len(), d.len());
 
-                ptr::copy_nonoverlapping(
-                    s.as_ptr(),
-                    d.as_mut_ptr(),
-                    l);
+                d[..l].copy_from_slice(&s[..l]);
             }
 
             src.advance(l);
@@ -271,17 +290,13 @@ pub trait BufMut {
         while off < src.len() {
             let cnt;
 
-            unsafe {
-                let dst = self.bytes_mut();
+            {
+                let dst = self.chunk_mut();
                 cnt = cmp::min(dst.len(), src.len() - off);
 
-                ptr::copy_nonoverlapping(
-                    src[off..].as_ptr(),
-                    dst.as_mut_ptr(),
-                    cnt);
+                dst[..cnt].copy_from_slice(&src[off..off + cnt]);
 
                 off += cnt;
-
             }
 
             unsafe { self.advance_mut(cnt); }
@@ -866,6 +881,199 @@ pub trait BufMut {
         self.put_u64_le(n.to_bits());
     }
 
+    /// Writes a fixed-size array of `N` bytes to `self`.
+    ///
+    /// The current position is advanced by `N`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::BufMut;
+    ///
+    /// let mut buf = vec![];
+    /// buf.put_array([1, 2, 3, 4]);
+    /// assert_eq!(buf, b"\x01\x02\x03\x04");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if there is not enough remaining capacity in
+    /// `self`.
+    fn put_array<const N: usize>(&mut self, src: [u8; N]) where Self: Sized {
+        self.put_slice(&src)
+    }
+
+    /// Writes the lowercase hex encoding of `src` to `self`.
+    ///
+    /// The current position is advanced by `2 * src.len()`. Use
+    /// [`Hex::encode_to`] for uppercase digits.
+    ///
+    /// [`Hex::encode_to`]: ../encoding/enum.Hex.html#method.encode_to
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::BufMut;
+    ///
+    /// let mut buf = vec![];
+    /// buf.put_hex(b"\xde\xad\xbe\xef");
+    /// assert_eq!(buf, b"deadbeef");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if there is not enough remaining capacity in
+    /// `self`.
+    fn put_hex(&mut self, src: &[u8]) {
+        Hex::Lower.encode_to(src, self)
+    }
+
+    /// Writes the base64 encoding of `src` to `self`.
+    ///
+    /// The current position is advanced by `encoding.encoded_len(src.len())`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::BufMut;
+    /// use bytes::encoding::Base64;
+    ///
+    /// let mut buf = vec![];
+    /// buf.put_base64(b"hello", Base64::Standard);
+    /// assert_eq!(buf, b"aGVsbG8=");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if there is not enough remaining capacity in
+    /// `self`.
+    fn put_base64(&mut self, src: &[u8], encoding: Base64) {
+        encoding.encode_to(src, self)
+    }
+
+    /// Writes an unsigned LEB128 varint to `self`, as used by protocol
+    /// buffers.
+    ///
+    /// The current position is advanced by the length of the varint, which is
+    /// between 1 and 10 bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::BufMut;
+    ///
+    /// let mut buf = vec![];
+    /// buf.put_uvarint(300);
+    /// assert_eq!(buf, b"\xac\x02");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if there is not enough remaining capacity in
+    /// `self`.
+    fn put_uvarint(&mut self, n: u64) {
+        let mut buf = [0; varint::MAX_LEN];
+        let len = varint::encode(n, &mut buf);
+        self.put_slice(&buf[..len]);
+    }
+
+    /// Writes an unsigned LEB128 varint to `self`.
+    ///
+    /// The current position is advanced by the length of the varint, which is
+    /// between 1 and 5 bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::BufMut;
+    ///
+    /// let mut buf = vec![];
+    /// buf.put_uvarint32(u32::max_value());
+    /// assert_eq!(buf, b"\xff\xff\xff\xff\x0f");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if there is not enough remaining capacity in
+    /// `self`.
+    fn put_uvarint32(&mut self, n: u32) {
+        self.put_uvarint(n as u64);
+    }
+
+    /// Writes a zigzag encoded signed varint to `self`, as used by the
+    /// protocol buffers `sint64` type.
+    ///
+    /// The current position is advanced by the length of the varint, which is
+    /// between 1 and 10 bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::BufMut;
+    ///
+    /// let mut buf = vec![];
+    /// buf.put_ivarint(-2);
+    /// assert_eq!(buf, b"\x03");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if there is not enough remaining capacity in
+    /// `self`.
+    fn put_ivarint(&mut self, n: i64) {
+        self.put_uvarint(varint::zigzag_encode(n));
+    }
+
+    /// Writes a zigzag encoded signed varint to `self`, as used by the
+    /// protocol buffers `sint32` type.
+    ///
+    /// The current position is advanced by the length of the varint, which is
+    /// between 1 and 5 bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::BufMut;
+    ///
+    /// let mut buf = vec![];
+    /// buf.put_ivarint32(i32::min_value());
+    /// assert_eq!(buf, b"\xff\xff\xff\xff\x0f");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if there is not enough remaining capacity in
+    /// `self`.
+    fn put_ivarint32(&mut self, n: i32) {
+        self.put_ivarint(n as i64);
+    }
+
+    /// Writes a length-prefixed field to `self`.
+    ///
+    /// The length of `src` is written using `prefix`, followed by `src`
+    /// itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::BufMut;
+    /// use bytes::buf::LenPrefix;
+    ///
+    /// let mut buf = vec![];
+    /// buf.put_prefixed(LenPrefix::U8, b"hello");
+    /// buf.put_prefixed(LenPrefix::Varint, b"world");
+    /// assert_eq!(buf, b"\x05hello\x05world");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the length of `src` does not fit in `prefix`,
+    /// or if there is not enough remaining capacity in `self`.
+    fn put_prefixed(&mut self, prefix: LenPrefix, src: &[u8]) {
+        prefix.put_len(self, src.len());
+        self.put_slice(src);
+    }
+
     /// Creates a "by reference" adaptor for this instance of `BufMut`.
     ///
     /// The returned adapter also implements `BufMut` and will simply borrow
@@ -874,6 +1082,7 @@ pub trait BufMut {
     /// # Examples
     ///
     /// ```
+    /// # #[cfg(feature = "std")] {
     /// use bytes::BufMut;
     /// use std::io;
     ///
@@ -890,6 +1099,7 @@ pub trait BufMut {
     /// } // drop our &mut reference so that we can use `buf` again
     ///
     /// assert_eq!(buf, &b"hello world"[..]);
+    /// # }
     /// ```
     fn by_ref(&mut self) -> &mut Self where Self: Sized {
         self
@@ -917,6 +1127,7 @@ pub trait BufMut {
     ///
     /// assert_eq!(*buf, b"hello world"[..]);
     /// ```
+    #[cfg(feature = "std")]
     fn writer(self) -> Writer<Self> where Self: Sized {
         super::writer::new(self)
     }
@@ -927,12 +1138,12 @@ impl<T: BufMut + ?Sized> BufMut for &mut T {
         (**self).remaining_mut()
     }
 
-    unsafe fn bytes_mut(&mut self) -> &mut [u8] {
-        (**self).bytes_mut()
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        (**self).chunk_mut()
     }
 
-    unsafe fn bytes_vectored_mut<'b>(&'b mut self, dst: &mut [IoSliceMut<'b>]) -> usize {
-        (**self).bytes_vectored_mut(dst)
+    fn chunks_vectored_mut<'b>(&'b mut self, dst: &mut [&'b mut UninitSlice]) -> usize {
+        (**self).chunks_vectored_mut(dst)
     }
 
     unsafe fn advance_mut(&mut self, cnt: usize) {
@@ -945,12 +1156,12 @@ impl<T: BufMut + ?Sized> BufMut for Box<T> {
         (**self).remaining_mut()
     }
 
-    unsafe fn bytes_mut(&mut self) -> &mut [u8] {
-        (**self).bytes_mut()
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        (**self).chunk_mut()
     }
 
-    unsafe fn bytes_vectored_mut<'b>(&'b mut self, dst: &mut [IoSliceMut<'b>]) -> usize {
-        (**self).bytes_vectored_mut(dst)
+    fn chunks_vectored_mut<'b>(&'b mut self, dst: &mut [&'b mut UninitSlice]) -> usize {
+        (**self).chunks_vectored_mut(dst)
     }
 
     unsafe fn advance_mut(&mut self, cnt: usize) {
@@ -965,14 +1176,14 @@ impl BufMut for &mut [u8] {
     }
 
     #[inline]
-    unsafe fn bytes_mut(&mut self) -> &mut [u8] {
-        self
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        UninitSlice::new(self)
     }
 
     #[inline]
     unsafe fn advance_mut(&mut self, cnt: usize) {
         // Lifetime dance taken from `impl Write for &mut [u8]`.
-        let (_, b) = std::mem::replace(self, &mut []).split_at_mut(cnt);
+        let (_, b) = mem::replace(self, &mut []).split_at_mut(cnt);
         *self = b;
     }
 }
@@ -997,9 +1208,7 @@ impl BufMut for Vec<u8> {
     }
 
     #[inline]
-    unsafe fn bytes_mut(&mut self) -> &mut [u8] {
-        use std::slice;
-
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
         if self.capacity() == self.len() {
             self.reserve(64); // Grow the vec
         }
@@ -1008,7 +1217,7 @@ impl BufMut for Vec<u8> {
         let len = self.len();
 
         let ptr = self.as_mut_ptr();
-        &mut slice::from_raw_parts_mut(ptr, cap)[len..]
+        unsafe { &mut UninitSlice::from_raw_parts_mut(ptr, cap)[len..] }
     }
 }
 
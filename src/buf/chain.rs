@@ -1,6 +1,7 @@
-use crate::{Buf, BufMut};
-use crate::buf::IntoIter;
-use std::io::{IoSlice, IoSliceMut};
+use crate::{Buf, BufMut, Bytes, BytesMut};
+use crate::buf::{IntoIter, UninitSlice};
+#[cfg(feature = "std")]
+use std::io::IoSlice;
 
 /// A `Chain` sequences two buffers.
 ///
@@ -160,6 +161,16 @@ impl<T, U> Buf for Chain<T, U>
         }
     }
 
+    fn bytes_at(&self, offset: usize) -> &[u8] {
+        let a_rem = self.a.remaining();
+
+        if offset < a_rem {
+            self.a.bytes_at(offset)
+        } else {
+            self.b.bytes_at(offset - a_rem)
+        }
+    }
+
     fn advance(&mut self, mut cnt: usize) {
         let a_rem = self.a.remaining();
 
@@ -178,6 +189,37 @@ impl<T, U> Buf for Chain<T, U>
         self.b.advance(cnt);
     }
 
+    fn peek_slice(&self, dst: &mut [u8]) {
+        let a_rem = self.a.remaining();
+
+        if a_rem >= dst.len() {
+            self.a.peek_slice(dst);
+            return;
+        }
+
+        let (a, b) = dst.split_at_mut(a_rem);
+        self.a.peek_slice(a);
+        self.b.peek_slice(b);
+    }
+
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        let a_rem = self.a.remaining();
+
+        if a_rem >= len {
+            self.a.copy_to_bytes(len)
+        } else if a_rem == 0 {
+            self.b.copy_to_bytes(len)
+        } else {
+            assert!(len - a_rem <= self.b.remaining(), "`len` greater than remaining");
+
+            let mut ret = BytesMut::with_capacity(len);
+            ret.put(self.a.copy_to_bytes(a_rem));
+            ret.put(self.b.copy_to_bytes(len - a_rem));
+            ret.freeze()
+        }
+    }
+
+    #[cfg(feature = "std")]
     fn bytes_vectored<'a>(&'a self, dst: &mut [IoSlice<'a>]) -> usize {
         let mut n = self.a.bytes_vectored(dst);
         n += self.b.bytes_vectored(&mut dst[n..]);
@@ -193,11 +235,11 @@ impl<T, U> BufMut for Chain<T, U>
         self.a.remaining_mut() + self.b.remaining_mut()
     }
 
-    unsafe fn bytes_mut(&mut self) -> &mut [u8] {
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
         if self.a.has_remaining_mut() {
-            self.a.bytes_mut()
+            self.a.chunk_mut()
         } else {
-            self.b.bytes_mut()
+            self.b.chunk_mut()
         }
     }
 
@@ -219,9 +261,9 @@ impl<T, U> BufMut for Chain<T, U>
         self.b.advance_mut(cnt);
     }
 
-    unsafe fn bytes_vectored_mut<'a>(&'a mut self, dst: &mut [IoSliceMut<'a>]) -> usize {
-        let mut n = self.a.bytes_vectored_mut(dst);
-        n += self.b.bytes_vectored_mut(&mut dst[n..]);
+    fn chunks_vectored_mut<'a>(&'a mut self, dst: &mut [&'a mut UninitSlice]) -> usize {
+        let mut n = self.a.chunks_vectored_mut(dst);
+        n += self.b.chunks_vectored_mut(&mut dst[n..]);
         n
     }
 }
@@ -0,0 +1,106 @@
+use super::{Buf, BufMut, UninitSlice};
+
+use core::cmp;
+
+use alloc::vec::Vec;
+use std::io::Cursor;
+
+// Returns the position of `cursor`, clamped to `len`.
+fn position<T>(cursor: &Cursor<T>, len: usize) -> usize {
+    cmp::min(cursor.position(), len as u64) as usize
+}
+
+impl<T: AsRef<[u8]>> Buf for Cursor<T> {
+    fn remaining(&self) -> usize {
+        let len = self.get_ref().as_ref().len();
+        len - position(self, len)
+    }
+
+    fn bytes(&self) -> &[u8] {
+        let slice = self.get_ref().as_ref();
+        &slice[position(self, slice.len())..]
+    }
+
+    fn bytes_at(&self, offset: usize) -> &[u8] {
+        let bytes = self.bytes();
+        &bytes[cmp::min(offset, bytes.len())..]
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        assert!(cnt <= self.remaining(), "cannot advance past `remaining`");
+
+        let pos = self.position() + cnt as u64;
+        self.set_position(pos);
+    }
+}
+
+impl BufMut for Cursor<&mut [u8]> {
+    fn remaining_mut(&self) -> usize {
+        let len = self.get_ref().len();
+        len - position(self, len)
+    }
+
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        let len = self.get_ref().len();
+        let pos = position(self, len);
+
+        UninitSlice::new(&mut self.get_mut()[pos..])
+    }
+
+    unsafe fn advance_mut(&mut self, cnt: usize) {
+        assert!(cnt <= self.remaining_mut(), "cannot advance past `remaining_mut`");
+
+        let pos = self.position() + cnt as u64;
+        self.set_position(pos);
+    }
+}
+
+/// Writing to a `Cursor<Vec<u8>>` overwrites the bytes at the cursor position
+/// and grows the vector once the end is reached, like `io::Write` does. If the
+/// position is past the end of the vector, the gap is filled with zeros.
+impl BufMut for Cursor<Vec<u8>> {
+    fn remaining_mut(&self) -> usize {
+        let pos = cmp::min(self.position(), usize::MAX as u64) as usize;
+        usize::MAX - pos
+    }
+
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        let pos = self.position() as usize;
+        let vec = self.get_mut();
+
+        if pos > vec.len() {
+            vec.resize(pos, 0);
+        }
+
+        if pos < vec.len() {
+            // Overwrite the existing data first
+            return UninitSlice::new(&mut vec[pos..]);
+        }
+
+        if vec.capacity() == vec.len() {
+            vec.reserve(64);
+        }
+
+        let (len, cap) = (vec.len(), vec.capacity());
+        let ptr = vec.as_mut_ptr();
+
+        unsafe { &mut UninitSlice::from_raw_parts_mut(ptr, cap)[len..] }
+    }
+
+    unsafe fn advance_mut(&mut self, cnt: usize) {
+        if cnt == 0 {
+            return;
+        }
+
+        let pos = self.position() as usize + cnt;
+        let vec = self.get_mut();
+
+        if pos > vec.len() {
+            // The bytes past the end were written through `chunk_mut`.
+            assert!(pos <= vec.capacity(), "cannot advance past the written bytes");
+            vec.set_len(pos);
+        }
+
+        self.set_position(pos as u64);
+    }
+}
@@ -1,5 +1,7 @@
 use crate::{Buf, BufMut, IntoBuf, Bytes, BytesMut};
 
+use alloc::vec::Vec;
+
 /// Conversion from a [`Buf`]
 ///
 /// Implementing `FromBuf` for a type defines how it is created from a buffer.
@@ -101,7 +103,9 @@ impl FromBuf for Bytes {
     fn from_buf<T>(buf: T) -> Self
         where T: IntoBuf
     {
-        BytesMut::from_buf(buf).freeze()
+        let mut buf = buf.into_buf();
+        let len = buf.remaining();
+        buf.copy_to_bytes(len)
     }
 }
 
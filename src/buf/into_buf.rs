@@ -1,6 +1,8 @@
-use super::{Buf};
+use super::{ArrayBuf, Buf};
 use crate::BytesMut;
 
+use alloc::{string::String, vec::Vec};
+
 /// Conversion into a `Buf`
 ///
 /// An `IntoBuf` implementation defines how to convert a value into a `Buf`.
@@ -112,18 +114,34 @@ impl<'a> IntoBuf for &'a String {
     }
 }
 
+impl<const N: usize> IntoBuf for [u8; N] {
+    type Buf = ArrayBuf<N>;
+
+    fn into_buf(self) -> Self::Buf {
+        ArrayBuf::new(self)
+    }
+}
+
+impl<'a, const N: usize> IntoBuf for &'a [u8; N] {
+    type Buf = &'a [u8];
+
+    fn into_buf(self) -> Self::Buf {
+        &self[..]
+    }
+}
+
 impl IntoBuf for u8 {
-    type Buf = Option<[u8; 1]>;
+    type Buf = ArrayBuf<1>;
 
     fn into_buf(self) -> Self::Buf {
-        Some([self])
+        ArrayBuf::new([self])
     }
 }
 
 impl IntoBuf for i8 {
-    type Buf = Option<[u8; 1]>;
+    type Buf = ArrayBuf<1>;
 
     fn into_buf(self) -> Self::Buf {
-        Some([self as u8; 1])
+        ArrayBuf::new([self as u8])
     }
 }
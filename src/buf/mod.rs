@@ -16,23 +16,39 @@
 //! [`Buf`]: trait.Buf.html
 //! [`BufMut`]: trait.BufMut.html
 
+mod array;
 mod buf;
 mod buf_mut;
+mod buf_list;
 mod from_buf;
 mod chain;
+#[cfg(feature = "std")]
+mod cursor;
 mod into_buf;
 mod iter;
+mod prefixed;
+#[cfg(feature = "std")]
 mod reader;
 mod take;
+mod uninit_slice;
+mod varint;
 mod vec_deque;
+#[cfg(feature = "std")]
 mod writer;
 
-pub use self::buf::Buf;
+pub use self::array::ArrayBuf;
+pub use self::buf::{Buf, TryGetError};
 pub use self::buf_mut::BufMut;
+pub use self::buf_list::BufList;
 pub use self::from_buf::FromBuf;
 pub use self::chain::Chain;
 pub use self::into_buf::IntoBuf;
 pub use self::iter::IntoIter;
+pub use self::prefixed::{LenPrefix, PrefixedError};
+#[cfg(feature = "std")]
 pub use self::reader::Reader;
 pub use self::take::Take;
+pub use self::uninit_slice::UninitSlice;
+pub use self::varint::VarintError;
+#[cfg(feature = "std")]
 pub use self::writer::Writer;
@@ -0,0 +1,225 @@
+use super::{Buf, BufMut, TryGetError, VarintError};
+use super::varint;
+
+use core::{cmp, fmt};
+
+/// The encoding of the length prefix of a length-prefixed field.
+///
+/// Used by [`Buf::get_prefixed_bytes`] and [`BufMut::put_prefixed`].
+///
+/// [`Buf::get_prefixed_bytes`]: trait.Buf.html#method.get_prefixed_bytes
+/// [`BufMut::put_prefixed`]: trait.BufMut.html#method.put_prefixed
+///
+/// # Examples
+///
+/// ```
+/// use bytes::{Buf, BufMut};
+/// use bytes::buf::LenPrefix;
+///
+/// let mut buf = vec![];
+/// buf.put_prefixed(LenPrefix::U16Be, b"hello");
+/// assert_eq!(buf, b"\x00\x05hello");
+///
+/// let mut buf = &buf[..];
+/// assert_eq!(buf.get_prefixed_bytes(LenPrefix::U16Be).unwrap(), &b"hello"[..]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LenPrefix {
+    /// An unsigned 8 bit length.
+    U8,
+    /// An unsigned 16 bit length in big-endian byte order.
+    U16Be,
+    /// An unsigned 16 bit length in little-endian byte order.
+    U16Le,
+    /// An unsigned 32 bit length in big-endian byte order.
+    U32Be,
+    /// An unsigned 32 bit length in little-endian byte order.
+    U32Le,
+    /// An unsigned 64 bit length in big-endian byte order.
+    U64Be,
+    /// An unsigned 64 bit length in little-endian byte order.
+    U64Le,
+    /// An unsigned LEB128 varint length, as used by protocol buffers.
+    Varint,
+}
+
+/// The error returned when reading a length-prefixed or delimited field
+/// fails.
+///
+/// When an error is returned, the cursor of the buffer is not advanced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrefixedError {
+    /// The buffer ended before the end of the field.
+    Truncated,
+
+    /// The declared length of the field exceeds the limit.
+    TooLong {
+        /// The declared length of the field. For a NUL-terminated string,
+        /// whose length is not known, this is the number of bytes that were
+        /// searched for the terminator.
+        len: u64,
+        /// The maximum length that was accepted.
+        limit: usize,
+    },
+
+    /// The length prefix is not a valid varint.
+    InvalidPrefix,
+
+    /// No NUL terminator was found before the end of the buffer.
+    Unterminated,
+}
+
+impl LenPrefix {
+    /// Returns the largest length that can be encoded with this prefix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::buf::LenPrefix;
+    ///
+    /// assert_eq!(LenPrefix::U8.max_len(), 255);
+    /// assert_eq!(LenPrefix::Varint.max_len(), u64::max_value());
+    /// ```
+    pub fn max_len(self) -> u64 {
+        match self {
+            LenPrefix::U8 => u8::max_value() as u64,
+            LenPrefix::U16Be | LenPrefix::U16Le => u16::max_value() as u64,
+            LenPrefix::U32Be | LenPrefix::U32Le => u32::max_value() as u64,
+            LenPrefix::U64Be | LenPrefix::U64Le | LenPrefix::Varint => u64::max_value(),
+        }
+    }
+
+    // Returns the size of a fixed width prefix.
+    fn fixed_len(self) -> Option<usize> {
+        match self {
+            LenPrefix::U8 => Some(1),
+            LenPrefix::U16Be | LenPrefix::U16Le => Some(2),
+            LenPrefix::U32Be | LenPrefix::U32Le => Some(4),
+            LenPrefix::U64Be | LenPrefix::U64Le => Some(8),
+            LenPrefix::Varint => None,
+        }
+    }
+
+    /// Returns the number of bytes used to encode `len` with this prefix.
+    pub(crate) fn encoded_len(self, len: usize) -> usize {
+        match self.fixed_len() {
+            Some(size) => size,
+            None => varint::encoded_len(len as u64),
+        }
+    }
+
+    fn is_le(self) -> bool {
+        match self {
+            LenPrefix::U16Le | LenPrefix::U32Le | LenPrefix::U64Le => true,
+            _ => false,
+        }
+    }
+
+    /// Reads the prefix from the start of `buf` without advancing it. Returns
+    /// the declared length and the size of the prefix.
+    pub(crate) fn peek_len<B: Buf + ?Sized>(self, buf: &B) -> Result<(u64, usize), PrefixedError> {
+        let size = match self.fixed_len() {
+            Some(size) => size,
+            None => {
+                return varint::peek(buf, 64).map_err(PrefixedError::from);
+            }
+        };
+
+        if buf.remaining() < size {
+            return Err(PrefixedError::Truncated);
+        }
+
+        let mut header = [0; 8];
+        buf.peek_slice(&mut header[..size]);
+
+        let mut header = &header[..size];
+
+        let len = if self.is_le() {
+            header.get_uint_le(size)
+        } else {
+            header.get_uint(size)
+        };
+
+        Ok((len, size))
+    }
+
+    /// Writes `len` to `buf` using this prefix.
+    pub(crate) fn put_len<B: BufMut + ?Sized>(self, buf: &mut B, len: usize) {
+        assert!(len as u64 <= self.max_len(), "length does not fit in the prefix");
+
+        match self.fixed_len() {
+            Some(size) if self.is_le() => buf.put_uint_le(len as u64, size),
+            Some(size) => buf.put_uint(len as u64, size),
+            None => buf.put_uvarint(len as u64),
+        }
+    }
+}
+
+/// Returns the position of the first NUL byte among the first `max` bytes of
+/// `buf`, without advancing it.
+pub(crate) fn find_nul<B: Buf + ?Sized>(buf: &B, max: usize) -> Option<usize> {
+    let mut off = 0;
+
+    while off < max {
+        let chunk = buf.bytes_at(off);
+
+        if chunk.is_empty() {
+            break;
+        }
+
+        let chunk = &chunk[..cmp::min(chunk.len(), max - off)];
+
+        if let Some(pos) = chunk.iter().position(|&b| b == 0) {
+            return Some(off + pos);
+        }
+
+        off += chunk.len();
+    }
+
+    // `bytes_at` ended early, so copy the rest instead.
+    let len = cmp::min(max, buf.remaining());
+
+    if off < len {
+        let mut rest = alloc::vec![0; len];
+        buf.peek_slice(&mut rest);
+
+        return rest[off..].iter().position(|&b| b == 0).map(|pos| off + pos);
+    }
+
+    None
+}
+
+impl fmt::Display for PrefixedError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            PrefixedError::Truncated => fmt.write_str("field is truncated"),
+            PrefixedError::TooLong { len, limit } => {
+                write!(fmt, "field length {} exceeds the limit of {}", len, limit)
+            }
+            PrefixedError::InvalidPrefix => fmt.write_str("invalid length prefix"),
+            PrefixedError::Unterminated => fmt.write_str("missing NUL terminator"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PrefixedError {}
+
+/// Allows `?` on the `try_get_*` methods in functions returning a
+/// `PrefixedError`. A short read means the field is truncated.
+impl From<TryGetError> for PrefixedError {
+    fn from(_: TryGetError) -> PrefixedError {
+        PrefixedError::Truncated
+    }
+}
+
+/// Allows `?` on the varint methods in functions returning a
+/// `PrefixedError`, treating the varint as a length prefix.
+impl From<VarintError> for PrefixedError {
+    fn from(err: VarintError) -> PrefixedError {
+        match err {
+            VarintError::Truncated => PrefixedError::Truncated,
+            VarintError::Overflow => PrefixedError::InvalidPrefix,
+        }
+    }
+}
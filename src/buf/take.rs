@@ -1,6 +1,6 @@
-use crate::Buf;
+use crate::{Buf, Bytes};
 
-use std::cmp;
+use core::cmp;
 
 /// A `Buf` adapter which limits the bytes read from an underlying buffer.
 ///
@@ -142,9 +142,31 @@ impl<T: Buf> Buf for Take<T> {
         &bytes[..cmp::min(bytes.len(), self.limit)]
     }
 
+    fn bytes_at(&self, offset: usize) -> &[u8] {
+        if offset >= self.limit {
+            return &[];
+        }
+
+        let bytes = self.inner.bytes_at(offset);
+        &bytes[..cmp::min(bytes.len(), self.limit - offset)]
+    }
+
     fn advance(&mut self, cnt: usize) {
         assert!(cnt <= self.limit);
         self.inner.advance(cnt);
         self.limit -= cnt;
     }
+
+    fn peek_slice(&self, dst: &mut [u8]) {
+        assert!(dst.len() <= self.limit, "cannot peek past `remaining`");
+        self.inner.peek_slice(dst);
+    }
+
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        assert!(len <= self.limit, "`len` greater than remaining");
+
+        let ret = self.inner.copy_to_bytes(len);
+        self.limit -= len;
+        ret
+    }
 }
@@ -0,0 +1,162 @@
+use super::{Buf, TryGetError};
+
+use core::{cmp, fmt};
+
+/// The maximum length of an encoded 64 bit varint.
+pub(crate) const MAX_LEN: usize = 10;
+
+/// The error returned when decoding a varint fails.
+///
+/// When an error is returned, the cursor of the buffer is not advanced.
+///
+/// # Examples
+///
+/// ```
+/// use bytes::Buf;
+/// use bytes::buf::VarintError;
+///
+/// let mut buf = &b"\x80\x80"[..];
+/// assert_eq!(Err(VarintError::Truncated), buf.get_uvarint());
+/// assert_eq!(2, buf.remaining());
+///
+/// let mut buf = &b"\xff\xff\xff\xff\x7f"[..];
+/// assert_eq!(Err(VarintError::Overflow), buf.get_uvarint32());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VarintError {
+    /// The buffer ended before the last byte of the varint.
+    Truncated,
+
+    /// The encoded value does not fit in the requested integer type.
+    Overflow,
+}
+
+impl fmt::Display for VarintError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            VarintError::Truncated => fmt.write_str("varint is truncated"),
+            VarintError::Overflow => fmt.write_str("varint overflows the integer type"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VarintError {}
+
+/// Allows `?` on the `try_get_*` methods in functions returning a
+/// `VarintError`. A short read means the varint is truncated.
+impl From<TryGetError> for VarintError {
+    fn from(_: TryGetError) -> VarintError {
+        VarintError::Truncated
+    }
+}
+
+/// Reads a varint of at most `bits` bits from the start of `buf`, without
+/// advancing it. Returns the value and the encoded length.
+pub(crate) fn peek<B: Buf + ?Sized>(buf: &B, bits: u32) -> Result<(u64, usize), VarintError> {
+    let src = buf.bytes();
+
+    match decode(src, bits) {
+        Err(VarintError::Truncated) if buf.remaining() > src.len() => {
+            // The varint spans several chunks, copy it out first.
+            let mut tmp = [0; MAX_LEN];
+            let len = cmp::min(buf.remaining(), MAX_LEN);
+
+            buf.peek_slice(&mut tmp[..len]);
+            decode(&tmp[..len], bits)
+        }
+        res => res,
+    }
+}
+
+fn decode(src: &[u8], bits: u32) -> Result<(u64, usize), VarintError> {
+    let max_len = ((bits + 6) / 7) as usize;
+    let mut value = 0;
+
+    for (i, &byte) in src.iter().take(max_len).enumerate() {
+        let shift = 7 * i as u32;
+
+        // The last byte may only hold the bits that are left, and may not
+        // have the continuation bit set.
+        if i == max_len - 1 && (byte as u64) >> (bits - shift) != 0 {
+            return Err(VarintError::Overflow);
+        }
+
+        value |= ((byte & 0x7f) as u64) << shift;
+
+        if byte & 0x80 == 0 {
+            return Ok((value, i + 1));
+        }
+    }
+
+    if src.len() >= max_len {
+        Err(VarintError::Overflow)
+    } else {
+        Err(VarintError::Truncated)
+    }
+}
+
+/// Encodes `value` into `dst`, returning the encoded length.
+pub(crate) fn encode(mut value: u64, dst: &mut [u8; MAX_LEN]) -> usize {
+    let mut i = 0;
+
+    while value >= 0x80 {
+        dst[i] = (value as u8) | 0x80;
+        value >>= 7;
+        i += 1;
+    }
+
+    dst[i] = value as u8;
+    i + 1
+}
+
+/// Returns the number of bytes needed to encode `value`.
+pub(crate) fn encoded_len(value: u64) -> usize {
+    let bits = 64 - (value | 1).leading_zeros() as usize;
+    (bits + 6) / 7
+}
+
+pub(crate) fn zigzag_encode(n: i64) -> u64 {
+    ((n << 1) ^ (n >> 63)) as u64
+}
+
+pub(crate) fn zigzag_decode(n: u64) -> i64 {
+    ((n >> 1) as i64) ^ -((n & 1) as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let values = [0, 1, 127, 128, 300, 16_383, 16_384, u32::max_value() as u64, u64::max_value()];
+
+        for &value in &values {
+            let mut buf = [0; MAX_LEN];
+            let len = encode(value, &mut buf);
+            assert_eq!(decode(&buf[..len], 64), Ok((value, len)));
+            assert_eq!(encoded_len(value), len);
+            assert_eq!(decode(&buf[..len - 1], 64), Err(VarintError::Truncated));
+        }
+    }
+
+    #[test]
+    fn zigzag() {
+        let values = [(0, 0), (-1, 1), (1, 2), (-2, 3), (i64::max_value(), u64::max_value() - 1), (i64::min_value(), u64::max_value())];
+
+        for &(n, encoded) in &values {
+            assert_eq!(zigzag_encode(n), encoded);
+            assert_eq!(zigzag_decode(encoded), n);
+        }
+    }
+
+    #[test]
+    fn overflow() {
+        assert_eq!(decode(b"\xff\xff\xff\xff\x0f", 32), Ok((u32::max_value() as u64, 5)));
+        assert_eq!(decode(b"\xff\xff\xff\xff\x10", 32), Err(VarintError::Overflow));
+        assert_eq!(decode(b"\xff\xff\xff\xff\xff\xff\xff\xff\xff\x01", 64), Ok((u64::max_value(), 10)));
+        assert_eq!(decode(b"\xff\xff\xff\xff\xff\xff\xff\xff\xff\x02", 64), Err(VarintError::Overflow));
+        assert_eq!(decode(b"\x80\x80\x80\x80\x80\x80\x80\x80\x80\x80\x00", 64), Err(VarintError::Overflow));
+    }
+}
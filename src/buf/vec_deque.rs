@@ -1,4 +1,6 @@
-use std::collections::VecDeque;
+use core::cmp;
+
+use alloc::collections::VecDeque;
 
 use super::Buf;
 
@@ -16,14 +18,36 @@ impl Buf for VecDeque<u8> {
         }
     }
 
+    fn bytes_at(&self, offset: usize) -> &[u8] {
+        let (s1, s2) = self.as_slices();
+
+        if offset < s1.len() {
+            &s1[offset..]
+        } else {
+            &s2[cmp::min(offset - s1.len(), s2.len())..]
+        }
+    }
+
     fn advance(&mut self, cnt: usize) {
         self.drain(..cnt);
     }
+
+    fn peek_slice(&self, dst: &mut [u8]) {
+        assert!(self.len() >= dst.len(), "cannot peek past `remaining`");
+
+        let (s1, s2) = self.as_slices();
+        let n = cmp::min(s1.len(), dst.len());
+
+        let (a, b) = dst.split_at_mut(n);
+        a.copy_from_slice(&s1[..n]);
+        b.copy_from_slice(&s2[..b.len()]);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::vec::Vec;
 
     #[test]
     fn hello_world() {
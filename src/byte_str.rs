@@ -0,0 +1,510 @@
+use crate::Bytes;
+
+use core::{cmp, fmt, hash, str};
+use core::borrow::Borrow;
+use core::ops::{Deref, RangeBounds};
+
+use alloc::string::String;
+
+/// A cheaply cloneable and sliceable UTF-8 string.
+///
+/// `ByteStr` wraps a [`Bytes`] whose contents are guaranteed to be valid
+/// UTF-8. It dereferences to `str`, and slicing or splitting it does not copy
+/// the data, just like `Bytes`. The boundaries passed to [`slice`],
+/// [`split_to`] and [`split_off`] must lie on `char` boundaries.
+///
+/// Converting from a `String` never copies, and converting back into a
+/// `String` reuses the allocation when the `ByteStr` is the only handle to a
+/// buffer that was created from a `String` or `Vec<u8>`.
+///
+/// [`Bytes`]: struct.Bytes.html
+/// [`slice`]: #method.slice
+/// [`split_to`]: #method.split_to
+/// [`split_off`]: #method.split_off
+///
+/// # Examples
+///
+/// ```
+/// use bytes::{ByteStr, Bytes};
+///
+/// let header = ByteStr::from_utf8(Bytes::from(&b"text/html; charset=utf-8"[..])).unwrap();
+///
+/// let mut value = header.clone();
+/// let mime = value.split_to(9);
+///
+/// assert_eq!(mime, "text/html");
+/// assert!(value.ends_with("utf-8"));
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ByteStr {
+    // Invariant: `bytes` holds valid UTF-8.
+    bytes: Bytes,
+}
+
+/// The error returned by [`ByteStr::from_utf8`] when the input is not valid
+/// UTF-8.
+///
+/// The original `Bytes` can be recovered with [`into_bytes`].
+///
+/// [`ByteStr::from_utf8`]: struct.ByteStr.html#method.from_utf8
+/// [`into_bytes`]: #method.into_bytes
+///
+/// # Examples
+///
+/// ```
+/// use bytes::{ByteStr, Bytes};
+///
+/// let err = ByteStr::from_utf8(Bytes::from_static(b"ab\xff")).unwrap_err();
+///
+/// assert_eq!(err.utf8_error().valid_up_to(), 2);
+/// assert_eq!(err.into_bytes(), &b"ab\xff"[..]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FromUtf8Error {
+    bytes: Bytes,
+    error: str::Utf8Error,
+}
+
+impl ByteStr {
+    /// Creates a new empty `ByteStr`.
+    ///
+    /// This does not allocate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::ByteStr;
+    ///
+    /// let s = ByteStr::new();
+    /// assert_eq!(s, "");
+    /// ```
+    #[inline]
+    pub fn new() -> ByteStr {
+        ByteStr {
+            bytes: Bytes::new(),
+        }
+    }
+
+    /// Creates a new `ByteStr` from a static string.
+    ///
+    /// The returned `ByteStr` will point directly to the static string. There
+    /// is no allocating or copying.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::ByteStr;
+    ///
+    /// let s = ByteStr::from_static("hello");
+    /// assert_eq!(s, "hello");
+    /// ```
+    #[inline]
+    pub fn from_static(s: &'static str) -> ByteStr {
+        ByteStr {
+            bytes: Bytes::from_static(s.as_bytes()),
+        }
+    }
+
+    /// Converts `Bytes` into a `ByteStr`, checking that it is valid UTF-8.
+    ///
+    /// The data is not copied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{ByteStr, Bytes};
+    ///
+    /// let s = ByteStr::from_utf8(Bytes::from_static("grüße".as_bytes())).unwrap();
+    /// assert_eq!(s, "grüße");
+    ///
+    /// assert!(ByteStr::from_utf8(Bytes::from_static(b"\xc3")).is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FromUtf8Error`, which holds the original `Bytes`, if `bytes`
+    /// is not valid UTF-8.
+    pub fn from_utf8(bytes: Bytes) -> Result<ByteStr, FromUtf8Error> {
+        match str::from_utf8(&bytes) {
+            Ok(_) => Ok(ByteStr { bytes: bytes }),
+            Err(error) => Err(FromUtf8Error {
+                bytes: bytes,
+                error: error,
+            }),
+        }
+    }
+
+    /// Converts `Bytes` into a `ByteStr` without checking that it is valid
+    /// UTF-8.
+    ///
+    /// # Safety
+    ///
+    /// `bytes` must be valid UTF-8.
+    #[inline]
+    pub unsafe fn from_utf8_unchecked(bytes: Bytes) -> ByteStr {
+        ByteStr { bytes: bytes }
+    }
+
+    /// Returns the string slice.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(&self.bytes) }
+    }
+
+    /// Returns a reference to the underlying `Bytes`.
+    #[inline]
+    pub fn as_bytes(&self) -> &Bytes {
+        &self.bytes
+    }
+
+    /// Consumes the `ByteStr`, returning the underlying `Bytes`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::ByteStr;
+    ///
+    /// let s = ByteStr::from_static("hello");
+    /// assert_eq!(s.into_bytes(), &b"hello"[..]);
+    /// ```
+    #[inline]
+    pub fn into_bytes(self) -> Bytes {
+        self.bytes
+    }
+
+    /// Returns a slice of self for the provided range.
+    ///
+    /// This will increment the reference count for the underlying memory and
+    /// return a new `ByteStr` handle set to the slice.
+    ///
+    /// This operation is `O(1)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::ByteStr;
+    ///
+    /// let a = ByteStr::from("hello world");
+    /// let b = a.slice(6..);
+    ///
+    /// assert_eq!(b, "world");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Requires that `begin <= end` and `end <= self.len()`, and that both
+    /// lie on `char` boundaries, otherwise slicing will panic.
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> ByteStr {
+        use core::ops::Bound;
+
+        let len = self.len();
+
+        let begin = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n + 1,
+            Bound::Unbounded => 0,
+        };
+
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n + 1,
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => len,
+        };
+
+        assert!(self.is_char_boundary(begin), "byte index {} is not a char boundary", begin);
+        assert!(self.is_char_boundary(end), "byte index {} is not a char boundary", end);
+
+        ByteStr {
+            bytes: self.bytes.slice(begin..end),
+        }
+    }
+
+    /// Returns a slice of self that is equivalent to the given `subset`.
+    ///
+    /// This turns a `&str` derived from `self`, for example by `str::trim` or
+    /// `str::split`, into a `ByteStr` without copying.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::ByteStr;
+    ///
+    /// let s = ByteStr::from("  hello  ");
+    /// let trimmed = s.slice_ref(s.trim());
+    ///
+    /// assert_eq!(trimmed, "hello");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Requires that the given `subset` is in fact contained within the
+    /// `ByteStr`, otherwise this function will panic.
+    pub fn slice_ref(&self, subset: &str) -> ByteStr {
+        // `subset` is a `&str`, so it starts and ends on `char` boundaries.
+        ByteStr {
+            bytes: self.bytes.slice_ref(subset.as_bytes()),
+        }
+    }
+
+    /// Splits the string into two at the given index.
+    ///
+    /// Afterwards `self` contains bytes `[0, at)`, and the returned `ByteStr`
+    /// contains bytes `[at, len)`.
+    ///
+    /// This is an `O(1)` operation that just increases the reference count and
+    /// sets a few indices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::ByteStr;
+    ///
+    /// let mut a = ByteStr::from("hello world");
+    /// let b = a.split_off(5);
+    ///
+    /// assert_eq!(a, "hello");
+    /// assert_eq!(b, " world");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len` or if `at` is not on a `char` boundary.
+    pub fn split_off(&mut self, at: usize) -> ByteStr {
+        assert!(self.is_char_boundary(at), "byte index {} is not a char boundary", at);
+
+        ByteStr {
+            bytes: self.bytes.split_off(at),
+        }
+    }
+
+    /// Splits the string into two at the given index.
+    ///
+    /// Afterwards `self` contains bytes `[at, len)`, and the returned
+    /// `ByteStr` contains bytes `[0, at)`.
+    ///
+    /// This is an `O(1)` operation that just increases the reference count and
+    /// sets a few indices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::ByteStr;
+    ///
+    /// let mut a = ByteStr::from("hello world");
+    /// let b = a.split_to(5);
+    ///
+    /// assert_eq!(a, " world");
+    /// assert_eq!(b, "hello");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len` or if `at` is not on a `char` boundary.
+    pub fn split_to(&mut self, at: usize) -> ByteStr {
+        assert!(self.is_char_boundary(at), "byte index {} is not a char boundary", at);
+
+        ByteStr {
+            bytes: self.bytes.split_to(at),
+        }
+    }
+
+    /// Shortens the string, keeping the first `len` bytes and dropping the
+    /// rest.
+    ///
+    /// If `len` is greater than the string's current length, this has no
+    /// effect.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is not on a `char` boundary.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            assert!(self.is_char_boundary(len), "byte index {} is not a char boundary", len);
+            self.bytes.truncate(len);
+        }
+    }
+
+    /// Clears the string, removing all data.
+    pub fn clear(&mut self) {
+        self.bytes.clear();
+    }
+}
+
+impl Deref for ByteStr {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for ByteStr {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<[u8]> for ByteStr {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl Borrow<str> for ByteStr {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl hash::Hash for ByteStr {
+    fn hash<H>(&self, state: &mut H) where H: hash::Hasher {
+        self.as_str().hash(state);
+    }
+}
+
+impl fmt::Debug for ByteStr {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), fmt)
+    }
+}
+
+impl fmt::Display for ByteStr {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), fmt)
+    }
+}
+
+impl From<String> for ByteStr {
+    fn from(src: String) -> ByteStr {
+        ByteStr {
+            bytes: Bytes::from(src),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for ByteStr {
+    fn from(src: &'a str) -> ByteStr {
+        ByteStr {
+            bytes: Bytes::from(src),
+        }
+    }
+}
+
+impl From<ByteStr> for Bytes {
+    fn from(src: ByteStr) -> Bytes {
+        src.bytes
+    }
+}
+
+impl From<ByteStr> for String {
+    fn from(src: ByteStr) -> String {
+        unsafe { String::from_utf8_unchecked(src.bytes.into_vec()) }
+    }
+}
+
+impl PartialEq<str> for ByteStr {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialOrd<str> for ByteStr {
+    fn partial_cmp(&self, other: &str) -> Option<cmp::Ordering> {
+        self.as_str().partial_cmp(other)
+    }
+}
+
+impl PartialEq<ByteStr> for str {
+    fn eq(&self, other: &ByteStr) -> bool {
+        *other == *self
+    }
+}
+
+impl PartialOrd<ByteStr> for str {
+    fn partial_cmp(&self, other: &ByteStr) -> Option<cmp::Ordering> {
+        self.partial_cmp(other.as_str())
+    }
+}
+
+impl PartialEq<String> for ByteStr {
+    fn eq(&self, other: &String) -> bool {
+        *self == other[..]
+    }
+}
+
+impl PartialOrd<String> for ByteStr {
+    fn partial_cmp(&self, other: &String) -> Option<cmp::Ordering> {
+        self.as_str().partial_cmp(&other[..])
+    }
+}
+
+impl PartialEq<ByteStr> for String {
+    fn eq(&self, other: &ByteStr) -> bool {
+        *other == *self
+    }
+}
+
+impl PartialOrd<ByteStr> for String {
+    fn partial_cmp(&self, other: &ByteStr) -> Option<cmp::Ordering> {
+        self[..].partial_cmp(other.as_str())
+    }
+}
+
+impl<'a> PartialEq<&'a str> for ByteStr {
+    fn eq(&self, other: &&'a str) -> bool {
+        *self == **other
+    }
+}
+
+impl<'a> PartialOrd<&'a str> for ByteStr {
+    fn partial_cmp(&self, other: &&'a str) -> Option<cmp::Ordering> {
+        self.as_str().partial_cmp(*other)
+    }
+}
+
+impl PartialEq<ByteStr> for &str {
+    fn eq(&self, other: &ByteStr) -> bool {
+        *other == *self
+    }
+}
+
+impl PartialOrd<ByteStr> for &str {
+    fn partial_cmp(&self, other: &ByteStr) -> Option<cmp::Ordering> {
+        (*self).partial_cmp(other.as_str())
+    }
+}
+
+/*
+ *
+ * ===== FromUtf8Error =====
+ *
+ */
+
+impl FromUtf8Error {
+    /// Returns the bytes that failed to convert.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns the `Bytes` that failed to convert.
+    pub fn into_bytes(self) -> Bytes {
+        self.bytes
+    }
+
+    /// Returns the `Utf8Error` describing where the conversion failed.
+    pub fn utf8_error(&self) -> str::Utf8Error {
+        self.error
+    }
+}
+
+impl fmt::Display for FromUtf8Error {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.error, fmt)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FromUtf8Error {}
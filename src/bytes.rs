@@ -1,13 +1,17 @@
 use crate::{Buf, BufMut, IntoBuf};
-use crate::buf::IntoIter;
+use crate::buf::{IntoIter, LenPrefix, UninitSlice};
 use crate::debug;
 
-use std::{cmp, fmt, mem, hash, slice, ptr, usize};
-use std::borrow::{Borrow, BorrowMut};
-use std::ops::{Deref, DerefMut, RangeBounds};
-use std::sync::atomic::{self, AtomicUsize, AtomicPtr};
-use std::sync::atomic::Ordering::{Relaxed, Acquire, Release, AcqRel};
-use std::iter::{FromIterator, Iterator};
+use core::{cmp, fmt, mem, hash, slice, ptr, usize};
+use core::alloc::Layout;
+use core::borrow::{Borrow, BorrowMut};
+use core::ops::{Deref, DerefMut, RangeBounds};
+use core::sync::atomic::{self, AtomicUsize, AtomicPtr};
+use core::sync::atomic::Ordering::{Relaxed, Acquire, Release, AcqRel};
+use core::iter::{FromIterator, Iterator};
+
+use alloc::{boxed::Box, string::String, vec::Vec};
+use alloc::alloc::handle_alloc_error;
 
 /// A reference counted contiguous slice of memory.
 ///
@@ -102,6 +106,7 @@ use std::iter::{FromIterator, Iterator};
 ///
 /// [^1]: Small enough: 31 bytes on 64 bit systems, 15 on 32 bit systems.
 ///
+#[repr(transparent)]
 pub struct Bytes {
     inner: Inner,
 }
@@ -148,10 +153,86 @@ pub struct Bytes {
 /// assert_eq!(&a[..], b"hello");
 /// assert_eq!(&b[..], b"hello");
 /// ```
+#[repr(transparent)]
 pub struct BytesMut {
     inner: Inner,
 }
 
+/// A non-owning reference to the memory of a `Bytes` handle.
+///
+/// `WeakBytes` is created by [`Bytes::downgrade`]. It does not keep the
+/// underlying memory alive: once all `Bytes` handles referencing the memory
+/// are dropped, the memory is released and [`upgrade`] returns `None`. This
+/// makes `WeakBytes` suitable for caches that should only hold onto a buffer
+/// while someone else is using it.
+///
+/// The upgraded `Bytes` covers the same window into the memory as the handle
+/// that was downgraded.
+///
+/// # Examples
+///
+/// ```
+/// use bytes::Bytes;
+///
+/// let a = Bytes::from(&b"mary had a little lamb, little lamb, little lamb"[..]);
+/// let weak = a.slice(5..40).downgrade();
+///
+/// assert_eq!(weak.upgrade().unwrap(), a[5..40]);
+///
+/// drop(a);
+/// assert!(weak.upgrade().is_none());
+/// ```
+///
+/// [`Bytes::downgrade`]: struct.Bytes.html#method.downgrade
+/// [`upgrade`]: #method.upgrade
+pub struct WeakBytes {
+    // When in arc mode, `inner` holds a weak reference to the `Shared`
+    // allocation, so it must never be dropped as a regular `Inner`.
+    inner: mem::ManuallyDrop<Inner>,
+}
+
+/// A placeholder for a length prefix in a `BytesMut`.
+///
+/// Created by [`BytesMut::put_len_slot`] and consumed by
+/// [`BytesMut::fill_len_slot`].
+///
+/// [`BytesMut::put_len_slot`]: struct.BytesMut.html#method.put_len_slot
+/// [`BytesMut::fill_len_slot`]: struct.BytesMut.html#method.fill_len_slot
+#[derive(Debug)]
+#[must_use = "the length slot must be filled with `fill_len_slot`"]
+pub struct LenSlot {
+    prefix: LenPrefix,
+    pos: usize,
+    size: usize,
+}
+
+/// The error type for fallible allocation methods such as
+/// [`BytesMut::try_reserve`].
+///
+/// [`BytesMut::try_reserve`]: struct.BytesMut.html#method.try_reserve
+///
+/// # Examples
+///
+/// ```
+/// use bytes::{BytesMut, TryReserveError};
+///
+/// let mut buf = BytesMut::from(&b"hello"[..]);
+///
+/// assert_eq!(buf.try_reserve(usize::max_value()), Err(TryReserveError::CapacityOverflow));
+/// assert_eq!(&buf[..], b"hello");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TryReserveError {
+    /// The requested capacity exceeds the maximum size of an allocation.
+    CapacityOverflow,
+
+    /// The memory allocator returned an error.
+    AllocError {
+        /// The layout of the allocation request that failed.
+        layout: Layout,
+    },
+}
+
 // Both `Bytes` and `BytesMut` are backed by `Inner` and functions are delegated
 // to `Inner` functions. The `Bytes` and `BytesMut` shims ensure that functions
 // that mutate the underlying buffer are only performed when the data range
@@ -211,6 +292,18 @@ pub struct BytesMut {
 // tracking that the `Bytes` instance represents a static buffer is stored in
 // the `Bytes` struct.
 //
+// ## Owned buffers
+//
+// `Bytes` can also reference memory that belongs to an arbitrary owner, which
+// is created with `Bytes::from_owner`. The owner is moved into an allocation
+// that starts with a `Shared` header, so the buffer is tracked exactly like an
+// `Arc` buffer. The header's vtable knows how to drop the owner and marks the
+// buffer as immutable, even when only a single handle references it.
+//
+// Buffers handed out by a `BytesPool` use the same mechanism, except that the
+// data is stored in `Shared::vec` as usual and, once the last strong handle is
+// released, the vector is handed back to the pool instead of being freed.
+//
 // # Struct layout
 //
 // Both `Bytes` and `BytesMut` are wrappers around `Inner`, which provides the
@@ -316,18 +409,88 @@ struct Inner {
 }
 
 // Thread-safe reference-counted container for the shared storage. This mostly
-// the same as `std::sync::Arc` but without the weak counter. The ref counting
-// fns are based on the ones found in `std`.
+// the same as `std::sync::Arc`. The ref counting fns are based on the ones
+// found in `std`.
+//
+// As with `Arc`, `weak_count` counts the `WeakBytes` handles plus one for all
+// of the strong handles collectively. The storage is dropped when `ref_count`
+// reaches zero, the `Shared` allocation itself once `weak_count` does.
 //
 // The main reason to use `Shared` instead of `std::sync::Arc` is that it ends
 // up making the overall code simpler and easier to reason about. This is due to
 // some of the logic around setting `Inner::arc` and other ways the `arc` field
 // is used. Using `Arc` ended up requiring a number of funky transmutes and
 // other shenanigans to make it work.
+//
+// `Shared` is also used as the header of larger allocations that store the
+// buffer somewhere other than `vec` (see `OwnedShared`). The `vtable` field
+// tracks how the allocation is released and whether `vec` holds the data.
 struct Shared {
     vec: Vec<u8>,
     original_capacity_repr: usize,
     ref_count: AtomicUsize,
+    weak_count: AtomicUsize,
+    vtable: &'static SharedVtable,
+}
+
+// Type-erased operations for a `Shared` allocation.
+struct SharedVtable {
+    // Drops the storage backing the buffer, leaving the allocation in place.
+    // Called once the last strong handle is released.
+    drop_data: unsafe fn(*mut Shared),
+    // Frees the allocation pointed to by the `Shared` pointer. The storage has
+    // already been dropped by `drop_data`.
+    dealloc: unsafe fn(*mut Shared),
+    // True if the buffer is stored in `Shared::vec`. Only then may a handle
+    // holding the sole reference mutate or reclaim the buffer.
+    vec_backed: bool,
+}
+
+// Storage for buffers created with `Bytes::from_owner`. The owner is kept
+// alive next to the `Shared` header, which must be the first field so that a
+// pointer to `OwnedShared<T>` can be used as a pointer to `Shared`.
+#[repr(C)]
+struct OwnedShared<T> {
+    shared: Shared,
+    owner: mem::ManuallyDrop<T>,
+}
+
+// Storage for buffers that are handed back to a `Recycle` implementation
+// (such as `BytesPool`) instead of being freed. The buffer lives in
+// `shared.vec`, so until the last strong handle is released it behaves exactly
+// like a regular `Arc` buffer.
+#[repr(C)]
+struct RecycledShared<R> {
+    shared: Shared,
+    recycler: mem::ManuallyDrop<R>,
+}
+
+// Takes ownership of the vector backing a recycled buffer once no handle
+// references it anymore.
+pub(crate) trait Recycle: Send + Sync + 'static {
+    fn recycle(&self, vec: Vec<u8>);
+}
+
+static VEC_VTABLE: SharedVtable = SharedVtable {
+    drop_data: drop_vec_data,
+    dealloc: dealloc_vec_shared,
+    vec_backed: true,
+};
+
+impl<T> OwnedShared<T> {
+    const VTABLE: SharedVtable = SharedVtable {
+        drop_data: drop_owned_data::<T>,
+        dealloc: dealloc_owned_shared::<T>,
+        vec_backed: false,
+    };
+}
+
+impl<R: Recycle> RecycledShared<R> {
+    const VTABLE: SharedVtable = SharedVtable {
+        drop_data: drop_recycled_data::<R>,
+        dealloc: dealloc_recycled_shared::<R>,
+        vec_backed: true,
+    };
 }
 
 // Buffer storage strategy flags.
@@ -455,6 +618,39 @@ impl Bytes {
         }
     }
 
+    /// Creates a new `Bytes` backed by the memory of `owner`.
+    ///
+    /// The returned `Bytes` points directly into the slice returned by
+    /// `owner.as_ref()`. There is no copying. `owner` is kept alive until the
+    /// last `Bytes` handle referencing it, including handles obtained through
+    /// `clone`, `slice`, `split_to` and `split_off`, is dropped.
+    ///
+    /// The slice returned by `owner.as_ref()` must not change for as long as
+    /// `owner` is alive.
+    ///
+    /// Since the memory belongs to `owner`, the returned `Bytes` can never be
+    /// converted to a `BytesMut` without copying.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use std::sync::Arc;
+    ///
+    /// let owner: Arc<[u8]> = Arc::from(&b"hello world"[..]);
+    ///
+    /// let b = Bytes::from_owner(owner.clone());
+    /// assert_eq!(&b[..], b"hello world");
+    /// assert_eq!(b.as_ptr(), owner.as_ptr());
+    /// ```
+    pub fn from_owner<T>(owner: T) -> Bytes
+        where T: AsRef<[u8]> + Send + Sync + 'static,
+    {
+        Bytes {
+            inner: Inner::from_owner(owner),
+        }
+    }
+
     /// Returns the number of bytes contained in this `Bytes`.
     ///
     /// # Examples
@@ -522,7 +718,7 @@ impl Bytes {
     /// Requires that `begin <= end` and `end <= self.len()`, otherwise slicing
     /// will panic.
     pub fn slice(&self, range: impl RangeBounds<usize>) -> Bytes {
-        use std::ops::Bound;
+        use core::ops::Bound;
 
         let len = self.len();
 
@@ -754,17 +950,86 @@ impl Bytes {
         }
     }
 
-    /// Acquires a mutable reference to the owned form of the data.
+    /// Returns a mutable reference to the data, copying it first if `self` is
+    /// not the only handle referencing the underlying memory.
     ///
-    /// Clones the data if it is not already owned.
-    pub fn to_mut(&mut self) -> &mut BytesMut {
+    /// This is a copy-on-write operation. If no other `Bytes`, `BytesMut` or
+    /// `WeakBytes` handle references the memory, it is mutated in place. This
+    /// is always the case for buffers created from a `Vec<u8>` that were never
+    /// cloned. Otherwise, only the bytes in the window of `self` are copied
+    /// into a new buffer, and the other handles are left untouched. Static
+    /// buffers and buffers created with `Bytes::from_owner` are always copied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let mut a = Bytes::from(&b"Mary had a little lamb, little lamb, little lamb..."[..]);
+    /// let ptr = a.as_ptr();
+    ///
+    /// // `a` is unique, no copy is made
+    /// a.make_mut()[0] = b'b';
+    /// assert_eq!(a.as_ptr(), ptr);
+    ///
+    /// // `a` is shared with `b`, so the data is copied
+    /// let b = a.clone();
+    /// a.make_mut()[0] = b'M';
+    ///
+    /// assert_eq!(&a[..4], b"Mary");
+    /// assert_eq!(&b[..4], b"bary");
+    /// ```
+    pub fn make_mut(&mut self) -> &mut BytesMut {
         if !self.inner.is_mut_safe() {
             let new = Bytes::from(&self[..]);
             *self = new;
         }
+
+        // `Bytes` and `BytesMut` are both transparent wrappers around `Inner`,
+        // and the handle is now safe to mutate.
         unsafe { &mut *(self as *mut Bytes as *mut BytesMut) }
     }
 
+    #[deprecated(since = "0.5.0", note = "use make_mut instead")]
+    #[doc(hidden)]
+    pub fn to_mut(&mut self) -> &mut BytesMut {
+        self.make_mut()
+    }
+
+    /// Converts `self` into a `BytesMut`, copying the data if `self` is not
+    /// the only handle referencing the underlying memory.
+    ///
+    /// Unlike `try_mut`, this always succeeds. As with `make_mut`, only the
+    /// bytes in the window of `self` are copied, and buffers created from a
+    /// `Vec<u8>` that are not shared are converted without copying.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let a = Bytes::from(&b"hello world"[..]);
+    /// let b = a.slice(6..);
+    ///
+    /// let mut b = b.into_mut();
+    /// b[0] = b'W';
+    ///
+    /// assert_eq!(&a[..], b"hello world");
+    /// assert_eq!(&b[..], b"World");
+    /// ```
+    pub fn into_mut(self) -> BytesMut {
+        match self.try_mut() {
+            Ok(bytes_mut) => bytes_mut,
+            Err(bytes) => BytesMut::from(&bytes[..]),
+        }
+    }
+
+    /// Converts `self` into a `Vec<u8>`, reusing the allocation if `self` is
+    /// the only handle to a buffer created from a `Vec<u8>`.
+    pub(crate) fn into_vec(self) -> Vec<u8> {
+        self.inner.into_vec()
+    }
+
     /// Appends given bytes to this object.
     ///
     /// If this `Bytes` object has not enough capacity, it is resized first.
@@ -837,6 +1102,41 @@ impl Bytes {
         }
     }
 
+    /// Creates a new [`WeakBytes`] referencing the memory of `self`.
+    ///
+    /// The returned handle does not keep the memory alive. Calling
+    /// [`WeakBytes::upgrade`] returns a `Bytes` with the same view as `self` as
+    /// long as at least one `Bytes` handle referencing the memory is alive.
+    ///
+    /// Inline and static buffers are not reference counted. For these, the
+    /// returned handle stores a copy of `self` and upgrading always succeeds.
+    /// A buffer that has not been shared yet is promoted to shared storage,
+    /// which allocates the reference count.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let a = Bytes::from(vec![0; 1024]);
+    /// let weak = a.downgrade();
+    ///
+    /// let b = weak.upgrade().unwrap();
+    /// assert_eq!(a.as_ptr(), b.as_ptr());
+    ///
+    /// drop(a);
+    /// drop(b);
+    /// assert!(weak.upgrade().is_none());
+    /// ```
+    ///
+    /// [`WeakBytes`]: struct.WeakBytes.html
+    /// [`WeakBytes::upgrade`]: struct.WeakBytes.html#method.upgrade
+    pub fn downgrade(&self) -> WeakBytes {
+        WeakBytes {
+            inner: self.inner.downgrade(),
+        }
+    }
+
     /// Returns an iterator over the bytes contained by the buffer.
     ///
     /// # Examples
@@ -852,7 +1152,7 @@ impl Bytes {
     /// assert_eq!(iter.next().map(|b| *b), Some(b'c'));
     /// assert_eq!(iter.next(), None);
     /// ```
-    pub fn iter<'a>(&'a self) -> std::slice::Iter<'a, u8> {
+    pub fn iter<'a>(&'a self) -> slice::Iter<'a, u8> {
         self.bytes().iter()
     }
 }
@@ -873,6 +1173,17 @@ impl Buf for Bytes {
         assert!(cnt <= self.inner.as_ref().len(), "cannot advance past `remaining`");
         unsafe { self.inner.set_start(cnt); }
     }
+
+    #[inline]
+    fn peek_slice(&self, dst: &mut [u8]) {
+        assert!(dst.len() <= self.len(), "cannot peek past `remaining`");
+        dst.copy_from_slice(&self.inner.as_ref()[..dst.len()]);
+    }
+
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        assert!(len <= self.len(), "`len` greater than remaining");
+        self.split_to(len)
+    }
 }
 
 impl Clone for Bytes {
@@ -1026,7 +1337,7 @@ impl IntoIterator for Bytes {
 
 impl<'a> IntoIterator for &'a Bytes {
     type Item = &'a u8;
-    type IntoIter = std::slice::Iter<'a, u8>;
+    type IntoIter = slice::Iter<'a, u8>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.as_ref().into_iter()
@@ -1065,6 +1376,21 @@ impl<'a> Extend<&'a u8> for Bytes {
     }
 }
 
+macro_rules! put_front_impl {
+    ($($name:ident, $typ:tt::$conv:ident, $desc:expr;)*) => {
+        $(
+            #[doc = concat!("Prepends ", $desc, " to this object.")]
+            ///
+            /// The buffer is reallocated if there is not enough headroom, see
+            /// `prepend_slice`.
+            #[inline]
+            pub fn $name(&mut self, n: $typ) {
+                self.prepend_slice(&n.$conv());
+            }
+        )*
+    };
+}
+
 /*
  *
  * ===== BytesMut =====
@@ -1102,6 +1428,84 @@ impl BytesMut {
         }
     }
 
+    /// Creates a new `BytesMut` with the specified capacity, returning an
+    /// error if the allocation fails.
+    ///
+    /// This is the fallible version of [`with_capacity`].
+    ///
+    /// [`with_capacity`]: #method.with_capacity
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::BytesMut;
+    ///
+    /// let buf = BytesMut::try_with_capacity(64).unwrap();
+    /// assert_eq!(buf.capacity(), 64);
+    ///
+    /// assert!(BytesMut::try_with_capacity(usize::max_value()).is_err());
+    /// ```
+    pub fn try_with_capacity(capacity: usize) -> Result<BytesMut, TryReserveError> {
+        Ok(BytesMut {
+            inner: Inner::try_with_capacity(capacity)?,
+        })
+    }
+
+    /// Creates a new, empty `BytesMut` with `headroom` bytes of space reserved
+    /// in front of the data and room for `capacity` bytes after it.
+    ///
+    /// The headroom is consumed by `prepend_slice`, `push_front` and the
+    /// `put_*_front` methods, which makes it possible to write a payload first
+    /// and then wrap it in protocol headers without copying it.
+    ///
+    /// The headroom is kept when the buffer is reallocated to make room at
+    /// the back. However, like space consumed by `advance`, it is also space
+    /// that `reserve` may reuse: if the headroom is large compared to the
+    /// capacity, the data can be moved to the front of the allocation instead
+    /// of reallocating, which leaves no headroom. To keep the headroom,
+    /// request enough `capacity` up front.
+    ///
+    /// The buffer is never stored inline.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{BufMut, BytesMut};
+    ///
+    /// let mut buf = BytesMut::with_headroom(8, 64);
+    /// assert_eq!(buf.headroom(), 8);
+    /// assert_eq!(buf.capacity(), 64);
+    ///
+    /// buf.put(&b"payload"[..]);
+    /// buf.put_u16_front(7);
+    /// buf.put_u8_front(1);
+    ///
+    /// assert_eq!(&buf[..], b"\x01\x00\x07payload");
+    /// assert_eq!(buf.headroom(), 5);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `headroom + capacity` overflows `usize`.
+    pub fn with_headroom(headroom: usize, capacity: usize) -> BytesMut {
+        let total = headroom.checked_add(capacity).expect("capacity overflow");
+
+        let mut inner = Inner::from_vec(Vec::with_capacity(total));
+        unsafe { inner.set_start(headroom); }
+
+        BytesMut {
+            inner: inner,
+        }
+    }
+
+    /// Creates a new `BytesMut` backed by `vec` that hands the vector to
+    /// `recycler` once it is no longer referenced.
+    pub(crate) fn from_recycled_vec<R: Recycle>(vec: Vec<u8>, recycler: R) -> BytesMut {
+        BytesMut {
+            inner: Inner::from_recycled_vec(vec, recycler),
+        }
+    }
+
     /// Creates a new `BytesMut` with default capacity.
     ///
     /// Resulting object has length 0 and unspecified capacity.
@@ -1468,6 +1872,56 @@ impl BytesMut {
         self.inner.reserve(additional)
     }
 
+    /// Tries to reserve capacity for at least `additional` more bytes to be
+    /// inserted into the given `BytesMut`.
+    ///
+    /// This behaves like [`reserve`], including reclaiming space in the
+    /// existing buffer, but returns an error instead of panicking or aborting
+    /// if the capacity overflows or the allocation fails. On error, the buffer
+    /// is left unchanged.
+    ///
+    /// [`reserve`]: #method.reserve
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::BytesMut;
+    ///
+    /// let mut buf = BytesMut::from(&b"hello"[..]);
+    ///
+    /// buf.try_reserve(64).unwrap();
+    /// assert!(buf.capacity() >= 69);
+    ///
+    /// assert!(buf.try_reserve(usize::max_value()).is_err());
+    /// assert_eq!(&buf[..], b"hello");
+    /// ```
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.inner.try_reserve(additional, false)
+    }
+
+    /// Tries to reserve capacity for exactly `additional` more bytes to be
+    /// inserted into the given `BytesMut`.
+    ///
+    /// Unlike [`try_reserve`], no extra space is requested to avoid frequent
+    /// reallocations. The allocator may still return more space than
+    /// requested. On error, the buffer is left unchanged.
+    ///
+    /// [`try_reserve`]: #method.try_reserve
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::BytesMut;
+    ///
+    /// let mut buf = BytesMut::from(&b"hello"[..]);
+    ///
+    /// buf.try_reserve_exact(64).unwrap();
+    /// assert!(buf.capacity() >= 69);
+    /// ```
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.inner.try_reserve(additional, true)
+    }
+
     /// Appends given bytes to this object.
     ///
     /// If this `BytesMut` object has not enough capacity, it is resized first.
@@ -1489,71 +1943,346 @@ impl BytesMut {
         self.put_slice(extend);
     }
 
-    /// Combine splitted BytesMut objects back as contiguous.
+    /// Appends given bytes to this object, returning an error if the buffer
+    /// has to be resized and the allocation fails.
     ///
-    /// If `BytesMut` objects were not contiguous originally, they will be extended.
+    /// On error, the buffer is left unchanged.
     ///
     /// # Examples
     ///
     /// ```
     /// use bytes::BytesMut;
     ///
-    /// let mut buf = BytesMut::with_capacity(64);
-    /// buf.extend_from_slice(b"aaabbbcccddd");
+    /// let mut buf = BytesMut::with_capacity(0);
+    /// buf.try_extend_from_slice(b"aaabbb").unwrap();
     ///
-    /// let splitted = buf.split_off(6);
     /// assert_eq!(b"aaabbb", &buf[..]);
-    /// assert_eq!(b"cccddd", &splitted[..]);
+    /// ```
+    pub fn try_extend_from_slice(&mut self, extend: &[u8]) -> Result<(), TryReserveError> {
+        self.try_reserve(extend.len())?;
+        self.put_slice(extend);
+        Ok(())
+    }
+
+    /// Returns the number of bytes that can be prepended without reallocating.
+    ///
+    /// This is the space in front of the data that is owned by this handle,
+    /// such as space reserved by `with_headroom` or consumed by `advance`.
+    /// While the underlying memory is shared with other handles, for example
+    /// after `split_to`, the headroom is zero.
+    ///
+    /// # Examples
     ///
-    /// buf.unsplit(splitted);
-    /// assert_eq!(b"aaabbbcccddd", &buf[..]);
     /// ```
-    pub fn unsplit(&mut self, other: BytesMut) {
-        if self.is_empty() {
-            *self = other;
+    /// use bytes::{Buf, BytesMut};
+    ///
+    /// let mut buf = BytesMut::with_headroom(4, 64);
+    /// buf.extend_from_slice(b"hello world");
+    ///
+    /// buf.advance(6);
+    /// assert_eq!(buf.headroom(), 10);
+    /// ```
+    pub fn headroom(&self) -> usize {
+        self.inner.headroom()
+    }
+
+    /// Reserves headroom for at least `additional` bytes to be prepended.
+    ///
+    /// If there is not enough headroom, the data is moved into a new
+    /// allocation with the same capacity after the data. The new headroom is
+    /// at least the length of the data, so that prepending repeatedly takes
+    /// amortized constant time per byte.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::BytesMut;
+    ///
+    /// let mut buf = BytesMut::from(&b"world"[..]);
+    /// buf.reserve_headroom(6);
+    /// assert!(buf.headroom() >= 6);
+    ///
+    /// buf.prepend_slice(b"hello ");
+    /// assert_eq!(&buf[..], b"hello world");
+    /// ```
+    pub fn reserve_headroom(&mut self, additional: usize) {
+        if self.inner.headroom() >= additional {
             return;
         }
 
-        if let Err(other_inner) = self.inner.try_unsplit(other.inner) {
-            self.extend_from_slice(other_inner.as_ref());
-        }
+        let headroom = cmp::max(additional, self.len());
+
+        let mut new = BytesMut::with_headroom(headroom, self.capacity());
+        new.put_slice(self);
+        *self = new;
     }
 
-    /// Returns an iterator over the bytes contained by the buffer.
+    /// Prepends the given bytes to this object.
+    ///
+    /// If there is enough headroom, the data is written in place and the
+    /// start of the buffer moves back by `src.len()` bytes. Otherwise the
+    /// buffer is reallocated first, see `reserve_headroom`.
     ///
     /// # Examples
     ///
     /// ```
-    /// use bytes::{Buf, BytesMut};
+    /// use bytes::BytesMut;
     ///
-    /// let buf = BytesMut::from(&b"abc"[..]);
-    /// let mut iter = buf.iter();
+    /// let mut buf = BytesMut::with_headroom(16, 64);
+    /// buf.extend_from_slice(b"world");
+    /// let ptr = buf.as_ptr();
     ///
-    /// assert_eq!(iter.next().map(|b| *b), Some(b'a'));
-    /// assert_eq!(iter.next().map(|b| *b), Some(b'b'));
-    /// assert_eq!(iter.next().map(|b| *b), Some(b'c'));
-    /// assert_eq!(iter.next(), None);
+    /// buf.prepend_slice(b"hello ");
+    ///
+    /// assert_eq!(&buf[..], b"hello world");
+    /// assert_eq!(buf[6..].as_ptr(), ptr);
     /// ```
-    pub fn iter<'a>(&'a self) -> std::slice::Iter<'a, u8> {
-        self.bytes().iter()
-    }
-}
+    pub fn prepend_slice(&mut self, src: &[u8]) {
+        self.reserve_headroom(src.len());
 
-impl Buf for BytesMut {
-    #[inline]
-    fn remaining(&self) -> usize {
-        self.len()
-    }
+        unsafe { self.inner.set_start_back(src.len()); }
 
-    #[inline]
-    fn bytes(&self) -> &[u8] {
-        &(self.inner.as_ref())
+        self[..src.len()].copy_from_slice(src);
     }
 
-    #[inline]
-    fn advance(&mut self, cnt: usize) {
-        assert!(cnt <= self.inner.as_ref().len(), "cannot advance past `remaining`");
-        unsafe { self.inner.set_start(cnt); }
+    /// Prepends a single byte to this object.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::BytesMut;
+    ///
+    /// let mut buf = BytesMut::with_headroom(1, 8);
+    /// buf.extend_from_slice(b"ello");
+    /// buf.push_front(b'h');
+    ///
+    /// assert_eq!(&buf[..], b"hello");
+    /// ```
+    pub fn push_front(&mut self, byte: u8) {
+        self.prepend_slice(&[byte]);
+    }
+
+    put_front_impl! {
+        put_u8_front, u8::to_be_bytes, "an unsigned 8 bit integer";
+        put_i8_front, i8::to_be_bytes, "a signed 8 bit integer";
+        put_u16_front, u16::to_be_bytes, "an unsigned 16 bit integer in big-endian byte order";
+        put_u16_le_front, u16::to_le_bytes, "an unsigned 16 bit integer in little-endian byte order";
+        put_i16_front, i16::to_be_bytes, "a signed 16 bit integer in big-endian byte order";
+        put_i16_le_front, i16::to_le_bytes, "a signed 16 bit integer in little-endian byte order";
+        put_u32_front, u32::to_be_bytes, "an unsigned 32 bit integer in big-endian byte order";
+        put_u32_le_front, u32::to_le_bytes, "an unsigned 32 bit integer in little-endian byte order";
+        put_i32_front, i32::to_be_bytes, "a signed 32 bit integer in big-endian byte order";
+        put_i32_le_front, i32::to_le_bytes, "a signed 32 bit integer in little-endian byte order";
+        put_u64_front, u64::to_be_bytes, "an unsigned 64 bit integer in big-endian byte order";
+        put_u64_le_front, u64::to_le_bytes, "an unsigned 64 bit integer in little-endian byte order";
+        put_i64_front, i64::to_be_bytes, "a signed 64 bit integer in big-endian byte order";
+        put_i64_le_front, i64::to_le_bytes, "a signed 64 bit integer in little-endian byte order";
+    }
+
+    /// Appends a placeholder for a length prefix to this object.
+    ///
+    /// The placeholder is filled in by `fill_len_slot` once the field that
+    /// follows has been written, which avoids computing the length of the
+    /// field up front. The slot refers to an offset into `self`, so `self`
+    /// must not be split or advanced before the slot is filled.
+    ///
+    /// For `LenPrefix::Varint`, a single byte is reserved. If the final
+    /// length needs more bytes, the field is moved to make room.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{BufMut, BytesMut};
+    /// use bytes::buf::LenPrefix;
+    ///
+    /// let mut buf = BytesMut::new();
+    ///
+    /// let slot = buf.put_len_slot(LenPrefix::U32Be);
+    /// buf.extend_from_slice(b"hello");
+    /// buf.fill_len_slot(slot);
+    ///
+    /// assert_eq!(&buf[..], b"\x00\x00\x00\x05hello");
+    /// ```
+    pub fn put_len_slot(&mut self, prefix: LenPrefix) -> LenSlot {
+        let pos = self.len();
+        let size = prefix.encoded_len(0);
+
+        self.resize(pos + size, 0);
+
+        LenSlot {
+            prefix: prefix,
+            pos: pos,
+            size: size,
+        }
+    }
+
+    /// Fills in a length prefix placeholder created by `put_len_slot`.
+    ///
+    /// The length written is the number of bytes appended to `self` after the
+    /// placeholder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::BytesMut;
+    /// use bytes::buf::LenPrefix;
+    ///
+    /// let mut buf = BytesMut::new();
+    ///
+    /// let slot = buf.put_len_slot(LenPrefix::Varint);
+    /// buf.extend_from_slice(&[0; 200]);
+    /// buf.fill_len_slot(slot);
+    ///
+    /// // The body was moved by one byte to make room for the prefix.
+    /// assert_eq!(&buf[..2], b"\xc8\x01");
+    /// assert_eq!(buf.len(), 202);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slot does not lie within `self`, or if the length does
+    /// not fit in the prefix.
+    pub fn fill_len_slot(&mut self, slot: LenSlot) {
+        let start = slot.pos + slot.size;
+        assert!(start <= self.len(), "length slot out of bounds");
+
+        let len = self.len() - start;
+        let size = slot.prefix.encoded_len(len);
+
+        if size > slot.size {
+            // Make room for the larger prefix by shifting the body.
+            let shift = size - slot.size;
+            let end = self.len();
+
+            self.resize(end + shift, 0);
+            self.copy_within(start..end, start + shift);
+        }
+
+        let mut dst = &mut self[slot.pos..slot.pos + size];
+        slot.prefix.put_len(&mut dst, len);
+    }
+
+    /// Appends a length-prefixed field to this object, with the field being
+    /// written by `f`.
+    ///
+    /// This is a closure form of `put_len_slot` and `fill_len_slot`: the
+    /// field is written once, directly after the prefix, and the prefix is
+    /// patched in place afterwards. Returns the value returned by `f`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{BufMut, BytesMut};
+    /// use bytes::buf::LenPrefix;
+    ///
+    /// let mut buf = BytesMut::new();
+    ///
+    /// buf.put_len_prefixed(LenPrefix::U16Be, |body| {
+    ///     body.put_u8(1);
+    ///
+    ///     // Prefixed fields can be nested
+    ///     body.put_len_prefixed(LenPrefix::U8, |inner| {
+    ///         inner.extend_from_slice(b"hello");
+    ///     });
+    /// });
+    ///
+    /// assert_eq!(&buf[..], b"\x00\x07\x01\x05hello");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the field does not fit in the prefix.
+    pub fn put_len_prefixed<F, R>(&mut self, prefix: LenPrefix, f: F) -> R
+        where F: FnOnce(&mut BytesMut) -> R,
+    {
+        let slot = self.put_len_slot(prefix);
+        let ret = f(self);
+        self.fill_len_slot(slot);
+        ret
+    }
+
+    /// Combine splitted BytesMut objects back as contiguous.
+    ///
+    /// If `BytesMut` objects were not contiguous originally, they will be extended.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::BytesMut;
+    ///
+    /// let mut buf = BytesMut::with_capacity(64);
+    /// buf.extend_from_slice(b"aaabbbcccddd");
+    ///
+    /// let splitted = buf.split_off(6);
+    /// assert_eq!(b"aaabbb", &buf[..]);
+    /// assert_eq!(b"cccddd", &splitted[..]);
+    ///
+    /// buf.unsplit(splitted);
+    /// assert_eq!(b"aaabbbcccddd", &buf[..]);
+    /// ```
+    pub fn unsplit(&mut self, other: BytesMut) {
+        if self.is_empty() {
+            *self = other;
+            return;
+        }
+
+        if let Err(other_inner) = self.inner.try_unsplit(other.inner) {
+            self.extend_from_slice(other_inner.as_ref());
+        }
+    }
+
+    /// Like `unsplit`, but returns `other` instead of copying it if the two
+    /// buffers are not contiguous.
+    pub(crate) fn try_unsplit(&mut self, other: BytesMut) -> Result<(), BytesMut> {
+        self.inner.try_unsplit(other.inner).map_err(|inner| BytesMut { inner: inner })
+    }
+
+    /// Returns an iterator over the bytes contained by the buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::{Buf, BytesMut};
+    ///
+    /// let buf = BytesMut::from(&b"abc"[..]);
+    /// let mut iter = buf.iter();
+    ///
+    /// assert_eq!(iter.next().map(|b| *b), Some(b'a'));
+    /// assert_eq!(iter.next().map(|b| *b), Some(b'b'));
+    /// assert_eq!(iter.next().map(|b| *b), Some(b'c'));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn iter<'a>(&'a self) -> slice::Iter<'a, u8> {
+        self.bytes().iter()
+    }
+}
+
+impl Buf for BytesMut {
+    #[inline]
+    fn remaining(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn bytes(&self) -> &[u8] {
+        &(self.inner.as_ref())
+    }
+
+    #[inline]
+    fn advance(&mut self, cnt: usize) {
+        assert!(cnt <= self.inner.as_ref().len(), "cannot advance past `remaining`");
+        unsafe { self.inner.set_start(cnt); }
+    }
+
+    #[inline]
+    fn peek_slice(&self, dst: &mut [u8]) {
+        assert!(dst.len() <= self.len(), "cannot peek past `remaining`");
+        dst.copy_from_slice(&self.inner.as_ref()[..dst.len()]);
+    }
+
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        assert!(len <= self.len(), "`len` greater than remaining");
+        self.split_to(len).freeze()
     }
 }
 
@@ -1572,11 +2301,11 @@ impl BufMut for BytesMut {
     }
 
     #[inline]
-    unsafe fn bytes_mut(&mut self) -> &mut [u8] {
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
         let len = self.len();
 
         // This will never panic as `len` can never become invalid
-        &mut self.inner.as_raw()[len..]
+        unsafe { &mut self.inner.as_raw()[len..] }
     }
 
     #[inline]
@@ -1585,10 +2314,8 @@ impl BufMut for BytesMut {
 
         let len = src.len();
 
-        unsafe {
-            self.bytes_mut()[..len].copy_from_slice(src);
-            self.advance_mut(len);
-        }
+        self.chunk_mut()[..len].copy_from_slice(src);
+        unsafe { self.advance_mut(len); }
     }
 
     #[inline]
@@ -1783,7 +2510,7 @@ impl IntoIterator for BytesMut {
 
 impl<'a> IntoIterator for &'a BytesMut {
     type Item = &'a u8;
-    type IntoIter = std::slice::Iter<'a, u8>;
+    type IntoIter = slice::Iter<'a, u8>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.as_ref().into_iter()
@@ -1798,10 +2525,8 @@ impl Extend<u8> for BytesMut {
         self.reserve(lower);
 
         for b in iter {
-            unsafe {
-                self.bytes_mut()[0] = b;
-                self.advance_mut(1);
-            }
+            self.chunk_mut().write_byte(0, b);
+            unsafe { self.advance_mut(1); }
         }
     }
 }
@@ -1812,6 +2537,119 @@ impl<'a> Extend<&'a u8> for BytesMut {
     }
 }
 
+/*
+ *
+ * ===== WeakBytes =====
+ *
+ */
+
+impl WeakBytes {
+    /// Attempts to create a `Bytes` handle to the referenced memory.
+    ///
+    /// Returns `None` if the memory has already been released, i.e. all
+    /// `Bytes` handles referencing it have been dropped. The returned `Bytes`
+    /// has the same view as the handle `self` was created from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let a = Bytes::from(vec![1, 2, 3]);
+    /// let weak = a.downgrade();
+    ///
+    /// assert_eq!(weak.upgrade().unwrap(), vec![1, 2, 3]);
+    ///
+    /// drop(a);
+    /// assert!(weak.upgrade().is_none());
+    /// ```
+    pub fn upgrade(&self) -> Option<Bytes> {
+        unsafe {
+            if self.inner.is_inline_or_static() {
+                return Some(Bytes {
+                    inner: self.inner.shallow_clone(false),
+                });
+            }
+
+            debug_assert!(self.inner.kind() == KIND_ARC);
+
+            let arc = self.inner.arc.load(Relaxed);
+
+            if !(*arc).try_increment_strong() {
+                return None;
+            }
+
+            Some(Bytes {
+                inner: ptr::read(&*self.inner),
+            })
+        }
+    }
+}
+
+impl Clone for WeakBytes {
+    fn clone(&self) -> WeakBytes {
+        unsafe {
+            if self.inner.kind() == KIND_ARC {
+                let arc = self.inner.arc.load(Relaxed);
+
+                if (*arc).weak_count.fetch_add(1, Relaxed) == usize::MAX {
+                    abort();
+                }
+            }
+
+            WeakBytes {
+                inner: mem::ManuallyDrop::new(ptr::read(&*self.inner)),
+            }
+        }
+    }
+}
+
+impl Drop for WeakBytes {
+    fn drop(&mut self) {
+        if self.inner.kind() == KIND_ARC {
+            release_weak(*self.inner.arc.get_mut());
+        }
+    }
+}
+
+impl fmt::Debug for WeakBytes {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "(WeakBytes)")
+    }
+}
+
+/*
+ *
+ * ===== TryReserveError =====
+ *
+ */
+
+impl TryReserveError {
+    // Returns the error for a failed attempt to allocate `cap` bytes.
+    fn for_capacity(cap: usize) -> TryReserveError {
+        match Layout::from_size_align(cap, 1) {
+            Ok(layout) => TryReserveError::AllocError { layout: layout },
+            Err(_) => TryReserveError::CapacityOverflow,
+        }
+    }
+}
+
+impl fmt::Display for TryReserveError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            TryReserveError::CapacityOverflow => {
+                fmt.write_str("memory allocation failed because the computed capacity exceeded the collection's maximum")
+            }
+            TryReserveError::AllocError { .. } => {
+                fmt.write_str("memory allocation failed because the memory allocator returned an error")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TryReserveError {}
+
 /*
  *
  * ===== Inner =====
@@ -1853,6 +2691,65 @@ impl Inner {
         }
     }
 
+    fn from_owner<T>(owner: T) -> Inner
+        where T: AsRef<[u8]> + Send + Sync + 'static,
+    {
+        let shared = Box::into_raw(Box::new(OwnedShared {
+            shared: Shared {
+                vec: Vec::new(),
+                original_capacity_repr: 0,
+                ref_count: AtomicUsize::new(1),
+                weak_count: AtomicUsize::new(1),
+                vtable: &OwnedShared::<T>::VTABLE,
+            },
+            owner: mem::ManuallyDrop::new(owner),
+        }));
+
+        // The pointer should be aligned, so this assert should always succeed.
+        debug_assert!(0 == (shared as usize & KIND_MASK));
+
+        // The owner has been moved to its final location, so the slice it
+        // references will not move for as long as the allocation is alive.
+        let (ptr, len) = unsafe {
+            let data = (*shared).owner.as_ref();
+            (data.as_ptr() as *mut u8, data.len())
+        };
+
+        Inner {
+            arc: AtomicPtr::new(shared as *mut Shared),
+            ptr: ptr,
+            len: len,
+            cap: len,
+        }
+    }
+
+    fn from_recycled_vec<R: Recycle>(mut vec: Vec<u8>, recycler: R) -> Inner {
+        let len = vec.len();
+        let cap = vec.capacity();
+        let ptr = vec.as_mut_ptr();
+
+        let shared = Box::into_raw(Box::new(RecycledShared {
+            shared: Shared {
+                vec: vec,
+                original_capacity_repr: original_capacity_to_repr(cap),
+                ref_count: AtomicUsize::new(1),
+                weak_count: AtomicUsize::new(1),
+                vtable: &RecycledShared::<R>::VTABLE,
+            },
+            recycler: mem::ManuallyDrop::new(recycler),
+        }));
+
+        // The pointer should be aligned, so this assert should always succeed.
+        debug_assert!(0 == (shared as usize & KIND_MASK));
+
+        Inner {
+            arc: AtomicPtr::new(shared as *mut Shared),
+            ptr: ptr,
+            len: len,
+            cap: cap,
+        }
+    }
+
     #[inline]
     fn with_capacity(capacity: usize) -> Inner {
         if capacity <= INLINE_CAP {
@@ -1867,6 +2764,15 @@ impl Inner {
         }
     }
 
+    #[inline]
+    fn try_with_capacity(capacity: usize) -> Result<Inner, TryReserveError> {
+        if capacity <= INLINE_CAP {
+            Ok(Inner::with_capacity(capacity))
+        } else {
+            try_vec_with_capacity(capacity).map(Inner::from_vec)
+        }
+    }
+
     /// Return a slice for the handle's view into the shared buffer
     #[inline]
     fn as_ref(&self) -> &[u8] {
@@ -1896,13 +2802,13 @@ impl Inner {
     /// Return a mutable slice for the handle's view into the shared buffer
     /// including potentially uninitialized bytes.
     #[inline]
-    unsafe fn as_raw(&mut self) -> &mut [u8] {
+    unsafe fn as_raw(&mut self) -> &mut UninitSlice {
         debug_assert!(!self.is_static());
 
         if self.is_inline() {
-            slice::from_raw_parts_mut(self.inline_ptr(), INLINE_CAP)
+            UninitSlice::from_raw_parts_mut(self.inline_ptr(), INLINE_CAP)
         } else {
-            slice::from_raw_parts_mut(self.ptr, self.cap)
+            UninitSlice::from_raw_parts_mut(self.ptr, self.cap)
         }
     }
 
@@ -2020,12 +2926,13 @@ impl Inner {
         unsafe {
             ptr = self.ptr.offset(self.len as isize);
         }
+        // Buffers backed by different owners may be adjacent in memory, so
+        // the `Shared` pointers must be compared as well.
         if ptr == other.ptr &&
            self.kind() == KIND_ARC &&
-           other.kind() == KIND_ARC
+           other.kind() == KIND_ARC &&
+           self.arc.load(Acquire) == other.arc.load(Acquire)
         {
-            debug_assert_eq!(self.arc.load(Acquire),
-                             other.arc.load(Acquire));
             // Contiguous blocks, just combine directly
             self.len += other.len;
             self.cap += other.cap;
@@ -2119,6 +3026,48 @@ impl Inner {
         }
     }
 
+    /// Returns the number of bytes in front of the view that are owned by
+    /// this handle and can be reclaimed with `set_start_back`.
+    fn headroom(&self) -> usize {
+        // `BytesMut` handles are never shallow cloned through `&self`, so the
+        // kind cannot change concurrently.
+        let arc = self.arc.load(Relaxed);
+
+        match arc as usize & KIND_MASK {
+            KIND_VEC => arc as usize >> VEC_POS_OFFSET,
+            KIND_ARC => unsafe {
+                let shared = &*arc;
+
+                // The bytes in front of the view may belong to other handles
+                // unless this one is unique.
+                if shared.vtable.vec_backed && shared.is_unique() {
+                    self.ptr as usize - shared.vec.as_ptr() as usize
+                } else {
+                    0
+                }
+            },
+            _ => 0,
+        }
+    }
+
+    /// Moves the start of the view back by `cnt` bytes, into the headroom.
+    unsafe fn set_start_back(&mut self, cnt: usize) {
+        assert!(cnt <= self.headroom());
+
+        if cnt == 0 {
+            return;
+        }
+
+        if self.kind() == KIND_VEC {
+            let (pos, prev) = self.uncoordinated_get_vec_pos();
+            self.uncoordinated_set_vec_pos(pos - cnt, prev);
+        }
+
+        self.ptr = self.ptr.offset(-(cnt as isize));
+        self.len += cnt;
+        self.cap += cnt;
+    }
+
     unsafe fn set_end(&mut self, end: usize) {
         debug_assert!(self.is_shared());
 
@@ -2152,11 +3101,69 @@ impl Inner {
             false
         } else {
             // Otherwise, the underlying buffer is potentially shared with other
-            // handles, so the ref_count needs to be checked.
-            unsafe { (**self.arc.get_mut()).is_unique() }
+            // handles, so the ref_count needs to be checked. Buffers that are
+            // not stored in `Shared::vec` belong to an owner and can never be
+            // mutated.
+            unsafe {
+                let shared = &**self.arc.get_mut();
+                shared.vtable.vec_backed && shared.is_unique()
+            }
         }
     }
 
+    /// Converts the handle into a `Vec<u8>` holding its window of the buffer.
+    ///
+    /// The allocation is reused when the handle is the sole owner of a buffer
+    /// that was created from a `Vec<u8>`, moving the window to the front of
+    /// the vector if needed. Otherwise, the window is copied.
+    fn into_vec(mut self) -> Vec<u8> {
+        let kind = self.kind();
+
+        if kind == KIND_VEC {
+            let (off, _) = self.uncoordinated_get_vec_pos();
+
+            unsafe {
+                // The `off` bytes in front of the view may never have been
+                // initialized, so the view is moved to the front instead of
+                // including them in the vector's length.
+                let base = self.ptr.offset(-(off as isize));
+                ptr::copy(self.ptr, base, self.len);
+
+                let vec = Vec::from_raw_parts(base, self.len, self.cap + off);
+
+                mem::forget(self);
+                return vec;
+            }
+        }
+
+        if kind == KIND_ARC {
+            unsafe {
+                let shared = *self.arc.get_mut();
+
+                // Buffers handed back to a recycler must go through it, so
+                // only plain vector storage can be taken over.
+                if ptr::eq((*shared).vtable, &VEC_VTABLE) && (*shared).is_unique() {
+                    let mut vec = mem::replace(&mut (*shared).vec, Vec::new());
+                    let off = self.ptr as usize - vec.as_ptr() as usize;
+
+                    // The length recorded in the vector is stale, as data may
+                    // have been written through other handles since it was
+                    // shared, and the bytes in front of the view may never
+                    // have been initialized. Only the view is kept, moved to
+                    // the front of the vector.
+                    debug_assert!(off + self.len <= vec.capacity());
+                    ptr::copy(self.ptr, vec.as_mut_ptr(), self.len);
+                    vec.set_len(self.len);
+
+                    // Dropping `self` releases the now empty `Shared`.
+                    return vec;
+                }
+            }
+        }
+
+        self.as_ref().to_vec()
+    }
+
     /// Increments the ref count. This should only be done if it is known that
     /// it can be done safely. As such, this fn is not public, instead other
     /// fns will use this one while maintaining the guarantees.
@@ -2189,6 +3196,32 @@ impl Inner {
     }
 
 
+    /// Creates a copy of `self` holding a weak reference to the shared buffer.
+    ///
+    /// Inline and static buffers are copied. Vec-backed buffers are promoted
+    /// to shared storage first.
+    fn downgrade(&self) -> mem::ManuallyDrop<Inner> {
+        unsafe {
+            // `shallow_clone` promotes vec-backed buffers, so afterwards the
+            // storage is either inline, static or arc.
+            let strong = self.shallow_clone(false);
+
+            if strong.kind() == KIND_ARC {
+                let arc = strong.arc.load(Relaxed);
+
+                if (*arc).weak_count.fetch_add(1, Relaxed) == usize::MAX {
+                    abort();
+                }
+
+                // Give up the strong reference acquired by `shallow_clone`.
+                // It cannot be the last one as `self` is still alive.
+                (*arc).ref_count.fetch_sub(1, Release);
+            }
+
+            mem::ManuallyDrop::new(strong)
+        }
+    }
+
     #[cold]
     unsafe fn shallow_clone_sync(&self, mut_self: bool) -> Inner {
         // The function requires `&self`, this means that `shallow_clone`
@@ -2254,6 +3287,8 @@ impl Inner {
             // for the new clone that will be returned from
             // `shallow_clone`.
             ref_count: AtomicUsize::new(2),
+            weak_count: AtomicUsize::new(1),
+            vtable: &VEC_VTABLE,
         });
 
         let shared = Box::into_raw(shared);
@@ -2305,24 +3340,38 @@ impl Inner {
 
     #[inline]
     fn reserve(&mut self, additional: usize) {
+        match self.try_reserve(additional, false) {
+            Ok(()) => {}
+            Err(TryReserveError::CapacityOverflow) => panic!("capacity overflow"),
+            Err(TryReserveError::AllocError { layout }) => handle_alloc_error(layout),
+        }
+    }
+
+    // Reserves room for `additional` more bytes. When `exact` is set, no extra
+    // capacity is requested to amortize future growth. On failure, `self` is
+    // left untouched.
+    fn try_reserve(&mut self, additional: usize, exact: bool) -> Result<(), TryReserveError> {
         let len = self.len();
         let rem = self.capacity() - len;
 
         if additional <= rem {
             // The handle can already store at least `additional` more bytes, so
             // there is no further work needed to be done.
-            return;
+            return Ok(());
         }
 
+        let required = match len.checked_add(additional) {
+            Some(required) => required,
+            None => return Err(TryReserveError::CapacityOverflow),
+        };
+
         let kind = self.kind();
 
         // Always check `inline` first, because if the handle is using inline
         // data storage, all of the `Inner` struct fields will be gibberish.
         if kind == KIND_INLINE {
-            let new_cap = len + additional;
-
             // Promote to a vector
-            let mut v = Vec::with_capacity(new_cap);
+            let mut v = try_vec_with_capacity(required)?;
             v.extend_from_slice(self.as_ref());
 
             self.ptr = v.as_mut_ptr();
@@ -2334,7 +3383,7 @@ impl Inner {
             self.arc = AtomicPtr::new(KIND_VEC as *mut Shared);
 
             mem::forget(v);
-            return;
+            return Ok(());
         }
 
         if kind == KIND_VEC {
@@ -2342,7 +3391,7 @@ impl Inner {
             // just copy the data backwards and reuse the already-allocated
             // space.
             //
-            // Otherwise, since backed by a vector, use `Vec::reserve`
+            // Otherwise, since backed by a vector, grow the `Vec`
             unsafe {
                 let (off, prev) = self.uncoordinated_get_vec_pos();
 
@@ -2364,17 +3413,35 @@ impl Inner {
                 } else {
                     // No space - allocate more
                     let mut v = rebuild_vec(self.ptr, self.len, self.cap, off);
-                    v.reserve(additional);
 
-                    // Update the info
+                    // The vector also holds the `off` bytes in front of the
+                    // view.
+                    let res = match required.checked_add(off) {
+                        Some(vec_required) => {
+                            let new_cap = if exact {
+                                vec_required
+                            } else {
+                                cmp::max(v.capacity().saturating_mul(2), vec_required)
+                            };
+
+                            v.try_reserve_exact(new_cap - v.len())
+                                .map_err(|_| TryReserveError::for_capacity(new_cap))
+                        }
+                        None => Err(TryReserveError::CapacityOverflow),
+                    };
+
+                    // Update the info. On failure, this restores the same
+                    // values.
                     self.ptr = v.as_mut_ptr().offset(off as isize);
                     self.len = v.len() - off;
                     self.cap = v.capacity() - off;
 
                     // Drop the vec reference
                     mem::forget(v);
+
+                    return res;
                 }
-                return;
+                return Ok(());
             }
         }
 
@@ -2386,7 +3453,7 @@ impl Inner {
         // allocating a new vector with the requested capacity.
         //
         // Compute the new capacity
-        let mut new_cap = len + additional;
+        let mut new_cap = required;
         let original_capacity;
         let original_capacity_repr;
 
@@ -2395,15 +3462,26 @@ impl Inner {
             original_capacity = original_capacity_from_repr(original_capacity_repr);
 
             // First, try to reclaim the buffer. This is possible if the current
-            // handle is the only outstanding handle pointing to the buffer.
-            if (*arc).is_unique() {
+            // handle is the only outstanding handle pointing to the buffer and
+            // the buffer is stored in `vec`.
+            if (*arc).vtable.vec_backed && (*arc).is_unique() {
                 // This is the only handle to the buffer. It can be reclaimed.
                 // However, before doing the work of copying data, check to make
                 // sure that the vector has enough capacity.
                 let v = &mut (*arc).vec;
+                let off = self.ptr as usize - v.as_ptr() as usize;
+
+                if v.capacity() - off >= new_cap {
+                    // The space after the view is sufficient. Extend the view
+                    // in place, which keeps the headroom.
+                    self.cap = v.capacity() - off;
+
+                    return Ok(());
+                }
 
                 if v.capacity() >= new_cap {
-                    // The capacity is sufficient, reclaim the buffer
+                    // The capacity is sufficient once the space in front of
+                    // the view is reclaimed, as in the `KIND_VEC` case.
                     let ptr = v.as_mut_ptr();
 
                     ptr::copy(self.ptr, ptr, len);
@@ -2411,7 +3489,7 @@ impl Inner {
                     self.ptr = ptr;
                     self.cap = v.capacity();
 
-                    return;
+                    return Ok(());
                 }
 
                 // The vector capacity is not sufficient. The reserve request is
@@ -2419,38 +3497,55 @@ impl Inner {
                 // than requested if `new_cap` is not much bigger than the current
                 // capacity.
                 //
-                // There are some situations, using `reserve_exact` that the
-                // buffer capacity could be below `original_capacity`, so do a
-                // check.
-                new_cap = cmp::max(
-                    cmp::max(v.capacity() << 1, new_cap),
-                    original_capacity);
-            } else {
+                // There are some situations, using `try_reserve_exact` that
+                // the buffer capacity could be below `original_capacity`, so
+                // do a check.
+                if !exact {
+                    new_cap = cmp::max(
+                        cmp::max(v.capacity().saturating_mul(2), new_cap),
+                        original_capacity);
+                }
+            } else if !exact {
                 new_cap = cmp::max(new_cap, original_capacity);
             }
         }
 
+        // Keep the headroom owned by this handle in the new vector, so that
+        // growing at the back does not lose space reserved at the front.
+        let headroom = cmp::min(self.headroom(), MAX_VEC_POS);
+
+        let total = match headroom.checked_add(new_cap) {
+            Some(total) => total,
+            None => return Err(TryReserveError::CapacityOverflow),
+        };
+
         // Create a new vector to store the data
-        let mut v = Vec::with_capacity(new_cap);
+        let mut v = try_vec_with_capacity(total)?;
 
-        // Copy the bytes
-        v.extend_from_slice(self.as_ref());
+        unsafe {
+            // Copy the bytes
+            let ptr = v.as_mut_ptr().offset(headroom as isize);
+            ptr::copy_nonoverlapping(self.ptr, ptr, len);
 
-        // Release the shared handle. This must be done *after* the bytes are
-        // copied.
-        release_shared(arc);
+            // Release the shared handle. This must be done *after* the bytes
+            // are copied.
+            release_shared(arc);
 
-        // Update self
-        self.ptr = v.as_mut_ptr();
-        self.len = v.len();
-        self.cap = v.capacity();
+            // Update self
+            self.ptr = ptr;
+            self.len = len;
+            self.cap = v.capacity() - headroom;
+        }
 
         let arc = (original_capacity_repr << ORIGINAL_CAPACITY_OFFSET) | KIND_VEC;
 
         self.arc = AtomicPtr::new(arc as *mut Shared);
+        self.uncoordinated_set_vec_pos(headroom, arc);
 
         // Forget the vector handle
         mem::forget(v);
+
+        Ok(())
     }
 
     /// Returns true if the buffer is stored inline
@@ -2563,6 +3658,13 @@ impl Inner {
     }
 }
 
+// Allocates a vector with room for exactly `cap` bytes.
+fn try_vec_with_capacity(cap: usize) -> Result<Vec<u8>, TryReserveError> {
+    let mut v = Vec::new();
+    v.try_reserve_exact(cap).map_err(|_| TryReserveError::for_capacity(cap))?;
+    Ok(v)
+}
+
 fn rebuild_vec(ptr: *mut u8, mut len: usize, mut cap: usize, off: usize) -> Vec<u8> {
     unsafe {
         let ptr = ptr.offset(-(off as isize));
@@ -2615,10 +3717,63 @@ fn release_shared(ptr: *mut Shared) {
         atomic::fence(Acquire);
 
         // Drop the data
-        Box::from_raw(ptr);
+        ((*ptr).vtable.drop_data)(ptr);
+
+        // No new `WeakBytes` can be created once the last strong handle is
+        // gone. If none are outstanding, the allocation can be freed without
+        // touching `weak_count` again.
+        if (*ptr).weak_count.load(Acquire) == 1 {
+            ((*ptr).vtable.dealloc)(ptr);
+        } else {
+            release_weak(ptr);
+        }
     }
 }
 
+fn release_weak(ptr: *mut Shared) {
+    unsafe {
+        if (*ptr).weak_count.fetch_sub(1, Release) != 1 {
+            return;
+        }
+
+        // Synchronizes with the `Release` decrement in other threads, see
+        // `release_shared`.
+        atomic::fence(Acquire);
+
+        ((*ptr).vtable.dealloc)(ptr);
+    }
+}
+
+unsafe fn drop_vec_data(ptr: *mut Shared) {
+    drop(mem::replace(&mut (*ptr).vec, Vec::new()));
+}
+
+unsafe fn dealloc_vec_shared(ptr: *mut Shared) {
+    drop(Box::from_raw(ptr));
+}
+
+unsafe fn drop_owned_data<T>(ptr: *mut Shared) {
+    mem::ManuallyDrop::drop(&mut (*(ptr as *mut OwnedShared<T>)).owner);
+}
+
+unsafe fn dealloc_owned_shared<T>(ptr: *mut Shared) {
+    // `owner` is wrapped in `ManuallyDrop`, so this only frees the memory.
+    drop(Box::from_raw(ptr as *mut OwnedShared<T>));
+}
+
+unsafe fn drop_recycled_data<R: Recycle>(ptr: *mut Shared) {
+    let shared = &mut *(ptr as *mut RecycledShared<R>);
+    let vec = mem::replace(&mut shared.shared.vec, Vec::new());
+
+    shared.recycler.recycle(vec);
+    mem::ManuallyDrop::drop(&mut shared.recycler);
+}
+
+unsafe fn dealloc_recycled_shared<R>(ptr: *mut Shared) {
+    // `recycler` has already been dropped by `drop_recycled_data`.
+    drop(Box::from_raw(ptr as *mut RecycledShared<R>));
+}
+
 impl Shared {
     fn is_unique(&self) -> bool {
         // The goal is to check if the current handle is the only handle
@@ -2631,7 +3786,37 @@ impl Shared {
         // are ordered before the `ref_count` is decremented. As such,
         // this `Acquire` will guarantee that those mutations are
         // visible to the current thread.
-        self.ref_count.load(Acquire) == 1
+        //
+        // A `WeakBytes` could be upgraded at any time, so the buffer is only
+        // unique if there are none. `weak_count` must be checked first: the
+        // caller holds a strong handle, so once no `WeakBytes` exist, none
+        // can be created by other threads. Checking `ref_count` first would
+        // race with a `WeakBytes` being upgraded and then dropped.
+        self.weak_count.load(Acquire) == 1 &&
+            self.ref_count.load(Acquire) == 1
+    }
+
+    // Increments `ref_count` unless it has already reached zero, in which
+    // case the storage has been dropped. Used to upgrade `WeakBytes`.
+    fn try_increment_strong(&self) -> bool {
+        let mut n = self.ref_count.load(Relaxed);
+
+        loop {
+            if n == 0 {
+                return false;
+            }
+
+            if n == usize::MAX {
+                abort();
+            }
+
+            // `Relaxed` is enough on failure as nothing is read from the
+            // storage in that case. `Acquire` on success matches `std`.
+            match self.ref_count.compare_exchange_weak(n, n + 1, Acquire, Relaxed) {
+                Ok(_) => return true,
+                Err(actual) => n = actual,
+            }
+        }
     }
 }
 
@@ -2992,20 +4177,29 @@ impl PartialEq<Bytes> for BytesMut
     }
 }
 
-// While there is `std::process:abort`, it's only available in Rust 1.17, and
-// our minimum supported version is currently 1.15. So, this acts as an abort
-// by triggering a double panic, which always aborts in Rust.
+#[cfg(feature = "std")]
+#[inline(never)]
+#[cold]
+fn abort() -> ! {
+    std::process::abort()
+}
+
+// `std::process::abort` is not available in `core`. Without `std`, this acts
+// as an abort by triggering a double panic, which always aborts in Rust.
+#[cfg(not(feature = "std"))]
 struct Abort;
 
+#[cfg(not(feature = "std"))]
 impl Drop for Abort {
     fn drop(&mut self) {
         panic!();
     }
 }
 
+#[cfg(not(feature = "std"))]
 #[inline(never)]
 #[cold]
-fn abort() {
+fn abort() -> ! {
     let _a = Abort;
     panic!();
 }
@@ -1,8 +1,10 @@
-use crate::{Buf, BufMut};
+use crate::{Buf, BufMut, Bytes};
+use crate::buf::UninitSlice;
 
 use either::Either;
 use either::Either::*;
-use std::io::{IoSlice, IoSliceMut};
+#[cfg(feature = "std")]
+use std::io::IoSlice;
 
 impl<L, R> Buf for Either<L, R>
 where
@@ -23,6 +25,14 @@ where
         }
     }
 
+    fn bytes_at(&self, offset: usize) -> &[u8] {
+        match *self {
+            Left(ref b) => b.bytes_at(offset),
+            Right(ref b) => b.bytes_at(offset),
+        }
+    }
+
+    #[cfg(feature = "std")]
     fn bytes_vectored<'a>(&'a self, dst: &mut [IoSlice<'a>]) -> usize {
         match *self {
             Left(ref b) => b.bytes_vectored(dst),
@@ -43,6 +53,20 @@ where
             Right(ref mut b) => b.copy_to_slice(dst),
         }
     }
+
+    fn peek_slice(&self, dst: &mut [u8]) {
+        match *self {
+            Left(ref b) => b.peek_slice(dst),
+            Right(ref b) => b.peek_slice(dst),
+        }
+    }
+
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        match *self {
+            Left(ref mut b) => b.copy_to_bytes(len),
+            Right(ref mut b) => b.copy_to_bytes(len),
+        }
+    }
 }
 
 impl<L, R> BufMut for Either<L, R>
@@ -57,17 +81,17 @@ where
         }
     }
 
-    unsafe fn bytes_mut(&mut self) -> &mut [u8] {
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
         match *self {
-            Left(ref mut b) => b.bytes_mut(),
-            Right(ref mut b) => b.bytes_mut(),
+            Left(ref mut b) => b.chunk_mut(),
+            Right(ref mut b) => b.chunk_mut(),
         }
     }
 
-    unsafe fn bytes_vectored_mut<'a>(&'a mut self, dst: &mut [IoSliceMut<'a>]) -> usize {
+    fn chunks_vectored_mut<'a>(&'a mut self, dst: &mut [&'a mut UninitSlice]) -> usize {
         match *self {
-            Left(ref mut b) => b.bytes_vectored_mut(dst),
-            Right(ref mut b) => b.bytes_vectored_mut(dst),
+            Left(ref mut b) => b.chunks_vectored_mut(dst),
+            Right(ref mut b) => b.chunks_vectored_mut(dst),
         }
     }
 
@@ -0,0 +1,662 @@
+//! Hex and base64 encoding of byte buffers.
+//!
+//! The encoders and decoders in this module write their output directly into
+//! a [`BufMut`], so no intermediate `String` or `Vec<u8>` is allocated. The
+//! most common uses are also available as methods:
+//!
+//! * [`BufMut::put_hex`] and [`BufMut::put_base64`] encode into a buffer.
+//! * [`Bytes::from_hex`] and [`Bytes::from_base64`] decode into a new `Bytes`.
+//! * `Bytes` and `BytesMut` implement `fmt::LowerHex` and `fmt::UpperHex`.
+//!
+//! [`BufMut`]: ../trait.BufMut.html
+//! [`BufMut::put_hex`]: ../trait.BufMut.html#method.put_hex
+//! [`BufMut::put_base64`]: ../trait.BufMut.html#method.put_base64
+//! [`Bytes::from_hex`]: ../struct.Bytes.html#method.from_hex
+//! [`Bytes::from_base64`]: ../struct.Bytes.html#method.from_base64
+//!
+//! # Examples
+//!
+//! ```
+//! use bytes::{BufMut, Bytes, BytesMut};
+//! use bytes::encoding::Base64;
+//!
+//! let hash = Bytes::from_static(b"\xde\xad\xbe\xef");
+//! assert_eq!(format!("{:x}", hash), "deadbeef");
+//!
+//! let mut json = BytesMut::new();
+//! json.put_slice(b"{\"data\":\"");
+//! json.put_base64(b"hello", Base64::Standard);
+//! json.put_slice(b"\"}");
+//!
+//! assert_eq!(json, &b"{\"data\":\"aGVsbG8=\"}"[..]);
+//! ```
+
+use crate::{BufMut, Bytes, BytesMut};
+
+use core::{fmt, str};
+
+const HEX_LOWER: &[u8; 16] = b"0123456789abcdef";
+const HEX_UPPER: &[u8; 16] = b"0123456789ABCDEF";
+
+const BASE64_STANDARD: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const BASE64_URL_SAFE: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+// Size of the stack buffer output is staged in before it is written out.
+const STAGING_LEN: usize = 128;
+
+/// Hex (base16) encoding.
+///
+/// Decoding accepts both lowercase and uppercase digits, whichever variant is
+/// used.
+///
+/// # Examples
+///
+/// ```
+/// use bytes::encoding::Hex;
+///
+/// let mut buf = vec![];
+/// Hex::Upper.encode_to(b"\x01\xab", &mut buf);
+/// assert_eq!(buf, b"01AB");
+///
+/// let mut dst = vec![];
+/// Hex::Lower.decode_to(&buf, &mut dst).unwrap();
+/// assert_eq!(dst, b"\x01\xab");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hex {
+    /// Encodes using lowercase digits.
+    Lower,
+    /// Encodes using uppercase digits.
+    Upper,
+}
+
+/// Base64 encoding, as defined in [RFC 4648].
+///
+/// Decoding accepts input with or without padding, whichever variant is used,
+/// but padding that is present must be correct. Line breaks and other
+/// whitespace are not accepted.
+///
+/// [RFC 4648]: https://tools.ietf.org/html/rfc4648
+///
+/// # Examples
+///
+/// ```
+/// use bytes::encoding::Base64;
+///
+/// let mut buf = vec![];
+/// Base64::UrlSafeNoPad.encode_to(b"\xfb\xff", &mut buf);
+/// assert_eq!(buf, b"-_8");
+///
+/// let mut dst = vec![];
+/// Base64::UrlSafe.decode_to(b"-_8=", &mut dst).unwrap();
+/// assert_eq!(dst, b"\xfb\xff");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Base64 {
+    /// The standard alphabet, using `+` and `/`, with padding.
+    Standard,
+    /// The standard alphabet, without padding.
+    StandardNoPad,
+    /// The URL and filename safe alphabet, using `-` and `_`, with padding.
+    UrlSafe,
+    /// The URL and filename safe alphabet, without padding.
+    UrlSafeNoPad,
+}
+
+/// The error returned when decoding hex or base64 input fails.
+///
+/// When an error is returned, nothing has been written to the destination.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The input contains a byte that is not valid at its position.
+    InvalidByte {
+        /// The offset of the byte in the input.
+        index: usize,
+        /// The value of the byte.
+        byte: u8,
+    },
+
+    /// The length of the input is not valid for the encoding.
+    InvalidLength,
+}
+
+/*
+ *
+ * ===== Hex =====
+ *
+ */
+
+impl Hex {
+    fn digits(self) -> &'static [u8; 16] {
+        match self {
+            Hex::Lower => HEX_LOWER,
+            Hex::Upper => HEX_UPPER,
+        }
+    }
+
+    /// Returns the length of the encoding of `len` bytes.
+    pub fn encoded_len(self, len: usize) -> usize {
+        len.checked_mul(2).expect("encoded length overflows usize")
+    }
+
+    /// Encodes `src` and writes the result to `dst`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if there is not enough remaining capacity in
+    /// `dst`.
+    pub fn encode_to<B: BufMut + ?Sized>(self, src: &[u8], dst: &mut B) {
+        assert!(dst.remaining_mut() >= self.encoded_len(src.len()), "buffer overflow");
+
+        let mut staging = [0; STAGING_LEN];
+
+        for chunk in src.chunks(STAGING_LEN / 2) {
+            let len = self.encode_chunk(chunk, &mut staging);
+            dst.put_slice(&staging[..len]);
+        }
+    }
+
+    /// Decodes `src` and writes the result to `dst`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, without writing to `dst`, if `src` is not valid hex.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if there is not enough remaining capacity in
+    /// `dst`.
+    pub fn decode_to<B: BufMut + ?Sized>(self, src: &[u8], dst: &mut B) -> Result<(), DecodeError> {
+        let len = decoded_hex_len(src)?;
+        assert!(dst.remaining_mut() >= len, "buffer overflow");
+
+        let mut staging = [0; STAGING_LEN];
+
+        for chunk in src.chunks(STAGING_LEN * 2) {
+            let mut n = 0;
+
+            for pair in chunk.chunks(2) {
+                // `src` has been validated already.
+                staging[n] = hex_value(pair[0]).unwrap() << 4 | hex_value(pair[1]).unwrap();
+                n += 1;
+            }
+
+            dst.put_slice(&staging[..n]);
+        }
+
+        Ok(())
+    }
+
+    // Encodes `src` into `dst`, which must be large enough.
+    fn encode_chunk(self, src: &[u8], dst: &mut [u8]) -> usize {
+        let digits = self.digits();
+
+        for (i, &b) in src.iter().enumerate() {
+            dst[2 * i] = digits[(b >> 4) as usize];
+            dst[2 * i + 1] = digits[(b & 0x0f) as usize];
+        }
+
+        2 * src.len()
+    }
+
+    fn fmt(self, src: &[u8], fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if fmt.alternate() {
+            fmt.write_str("0x")?;
+        }
+
+        let mut staging = [0; STAGING_LEN];
+
+        for chunk in src.chunks(STAGING_LEN / 2) {
+            let len = self.encode_chunk(chunk, &mut staging);
+
+            // Hex digits are ASCII.
+            fmt.write_str(unsafe { str::from_utf8_unchecked(&staging[..len]) })?;
+        }
+
+        Ok(())
+    }
+}
+
+fn hex_value(b: u8) -> Option<u8> {
+    match b {
+        b'0'..=b'9' => Some(b - b'0'),
+        b'a'..=b'f' => Some(b - b'a' + 10),
+        b'A'..=b'F' => Some(b - b'A' + 10),
+        _ => None,
+    }
+}
+
+// Validates `src` and returns the length of the decoded data.
+fn decoded_hex_len(src: &[u8]) -> Result<usize, DecodeError> {
+    if src.len() % 2 != 0 {
+        return Err(DecodeError::InvalidLength);
+    }
+
+    for (index, &byte) in src.iter().enumerate() {
+        if hex_value(byte).is_none() {
+            return Err(DecodeError::InvalidByte { index: index, byte: byte });
+        }
+    }
+
+    Ok(src.len() / 2)
+}
+
+/*
+ *
+ * ===== Base64 =====
+ *
+ */
+
+impl Base64 {
+    fn is_url_safe(self) -> bool {
+        match self {
+            Base64::UrlSafe | Base64::UrlSafeNoPad => true,
+            Base64::Standard | Base64::StandardNoPad => false,
+        }
+    }
+
+    fn is_padded(self) -> bool {
+        match self {
+            Base64::Standard | Base64::UrlSafe => true,
+            Base64::StandardNoPad | Base64::UrlSafeNoPad => false,
+        }
+    }
+
+    fn alphabet(self) -> &'static [u8; 64] {
+        if self.is_url_safe() {
+            BASE64_URL_SAFE
+        } else {
+            BASE64_STANDARD
+        }
+    }
+
+    fn value(self, b: u8) -> Option<u8> {
+        match b {
+            b'A'..=b'Z' => Some(b - b'A'),
+            b'a'..=b'z' => Some(b - b'a' + 26),
+            b'0'..=b'9' => Some(b - b'0' + 52),
+            b'+' if !self.is_url_safe() => Some(62),
+            b'/' if !self.is_url_safe() => Some(63),
+            b'-' if self.is_url_safe() => Some(62),
+            b'_' if self.is_url_safe() => Some(63),
+            _ => None,
+        }
+    }
+
+    /// Returns the length of the encoding of `len` bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::encoding::Base64;
+    ///
+    /// assert_eq!(Base64::Standard.encoded_len(5), 8);
+    /// assert_eq!(Base64::StandardNoPad.encoded_len(5), 7);
+    /// ```
+    pub fn encoded_len(self, len: usize) -> usize {
+        let full = (len / 3).checked_mul(4).expect("encoded length overflows usize");
+
+        let rem = match len % 3 {
+            0 => 0,
+            _ if self.is_padded() => 4,
+            n => n + 1,
+        };
+
+        full.checked_add(rem).expect("encoded length overflows usize")
+    }
+
+    /// Encodes `src` and writes the result to `dst`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if there is not enough remaining capacity in
+    /// `dst`.
+    pub fn encode_to<B: BufMut + ?Sized>(self, src: &[u8], dst: &mut B) {
+        assert!(dst.remaining_mut() >= self.encoded_len(src.len()), "buffer overflow");
+
+        let alphabet = self.alphabet();
+        let mut staging = [0; STAGING_LEN];
+        let mut n = 0;
+
+        for group in src.chunks(3) {
+            let b0 = group[0];
+            let b1 = group.get(1).cloned().unwrap_or(0);
+            let b2 = group.get(2).cloned().unwrap_or(0);
+
+            let out = [
+                alphabet[(b0 >> 2) as usize],
+                alphabet[((b0 & 0x03) << 4 | b1 >> 4) as usize],
+                alphabet[((b1 & 0x0f) << 2 | b2 >> 6) as usize],
+                alphabet[(b2 & 0x3f) as usize],
+            ];
+
+            let len = group.len() + 1;
+            staging[n..n + len].copy_from_slice(&out[..len]);
+            n += len;
+
+            if len < 4 && self.is_padded() {
+                for b in &mut staging[n..n + 4 - len] {
+                    *b = b'=';
+                }
+
+                n += 4 - len;
+            }
+
+            if n + 4 > STAGING_LEN {
+                dst.put_slice(&staging[..n]);
+                n = 0;
+            }
+        }
+
+        dst.put_slice(&staging[..n]);
+    }
+
+    /// Decodes `src` and writes the result to `dst`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, without writing to `dst`, if `src` is not valid
+    /// base64 for this alphabet.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if there is not enough remaining capacity in
+    /// `dst`.
+    pub fn decode_to<B: BufMut + ?Sized>(self, src: &[u8], dst: &mut B) -> Result<(), DecodeError> {
+        let data = self.strip_padding(src)?;
+        let len = self.decoded_len(data)?;
+        assert!(dst.remaining_mut() >= len, "buffer overflow");
+
+        let mut staging = [0; STAGING_LEN];
+        let mut n = 0;
+
+        for group in data.chunks(4) {
+            let mut acc = 0u32;
+
+            for (i, &b) in group.iter().enumerate() {
+                // `data` has been validated already.
+                acc |= (self.value(b).unwrap() as u32) << (18 - 6 * i);
+            }
+
+            let out = [(acc >> 16) as u8, (acc >> 8) as u8, acc as u8];
+            let len = group.len() - 1;
+
+            staging[n..n + len].copy_from_slice(&out[..len]);
+            n += len;
+
+            if n + 3 > STAGING_LEN {
+                dst.put_slice(&staging[..n]);
+                n = 0;
+            }
+        }
+
+        dst.put_slice(&staging[..n]);
+        Ok(())
+    }
+
+    // Returns `src` without its padding, checking that the padding is valid.
+    fn strip_padding(self, src: &[u8]) -> Result<&[u8], DecodeError> {
+        let pad = src.iter().rev().take(2).take_while(|&&b| b == b'=').count();
+
+        if pad == 0 {
+            return Ok(src);
+        }
+
+        let data = &src[..src.len() - pad];
+
+        if src.len() % 4 != 0 || data.len() % 4 != 4 - pad {
+            return Err(DecodeError::InvalidLength);
+        }
+
+        Ok(data)
+    }
+
+    // Validates unpadded `data` and returns the length of the decoded data.
+    fn decoded_len(self, data: &[u8]) -> Result<usize, DecodeError> {
+        for (index, &byte) in data.iter().enumerate() {
+            if self.value(byte).is_none() {
+                return Err(DecodeError::InvalidByte { index: index, byte: byte });
+            }
+        }
+
+        let rem = data.len() % 4;
+
+        if rem == 1 {
+            return Err(DecodeError::InvalidLength);
+        }
+
+        // The bits of the last digit that do not make up a whole byte must be
+        // zero, otherwise the encoding is not canonical.
+        if rem != 0 {
+            let index = data.len() - 1;
+            let byte = data[index];
+            let mask = if rem == 2 { 0x0f } else { 0x03 };
+
+            if self.value(byte).unwrap() & mask != 0 {
+                return Err(DecodeError::InvalidByte { index: index, byte: byte });
+            }
+        }
+
+        Ok(data.len() / 4 * 3 + rem.saturating_sub(1))
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            DecodeError::InvalidByte { index, byte } => {
+                write!(fmt, "invalid byte 0x{:02x} at offset {}", byte, index)
+            }
+            DecodeError::InvalidLength => fmt.write_str("invalid input length"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+/*
+ *
+ * ===== Bytes =====
+ *
+ */
+
+impl Bytes {
+    /// Decodes a hex string into a new `Bytes`.
+    ///
+    /// Both lowercase and uppercase digits are accepted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let hash = Bytes::from_hex("DEADbeef").unwrap();
+    /// assert_eq!(hash, &b"\xde\xad\xbe\xef"[..]);
+    ///
+    /// assert!(Bytes::from_hex("abc").is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `src` is not valid hex.
+    pub fn from_hex(src: impl AsRef<[u8]>) -> Result<Bytes, DecodeError> {
+        let src = src.as_ref();
+
+        // Every two digits decode to a byte. Invalid input is reported by
+        // `decode_to` before anything is written.
+        let mut buf = BytesMut::with_capacity(src.len() / 2);
+
+        Hex::Lower.decode_to(src, &mut buf)?;
+        Ok(buf.freeze())
+    }
+
+    /// Decodes a base64 string into a new `Bytes`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use bytes::encoding::Base64;
+    ///
+    /// let data = Bytes::from_base64("aGVsbG8=", Base64::Standard).unwrap();
+    /// assert_eq!(data, &b"hello"[..]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `src` is not valid base64 for `encoding`.
+    pub fn from_base64(src: impl AsRef<[u8]>, encoding: Base64) -> Result<Bytes, DecodeError> {
+        let src = src.as_ref();
+
+        // Every four digits decode to three bytes, and a partial group to at
+        // most two. Invalid input is reported by `decode_to` before anything
+        // is written.
+        let mut buf = BytesMut::with_capacity(src.len() / 4 * 3 + src.len() % 4);
+
+        encoding.decode_to(src, &mut buf)?;
+        Ok(buf.freeze())
+    }
+}
+
+impl fmt::LowerHex for Bytes {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Hex::Lower.fmt(self, fmt)
+    }
+}
+
+impl fmt::UpperHex for Bytes {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Hex::Upper.fmt(self, fmt)
+    }
+}
+
+impl fmt::LowerHex for BytesMut {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Hex::Lower.fmt(self, fmt)
+    }
+}
+
+impl fmt::UpperHex for BytesMut {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Hex::Upper.fmt(self, fmt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::vec::Vec;
+
+    #[test]
+    fn base64_rfc4648_vectors() {
+        let vectors: &[(&[u8], &[u8])] = &[
+            (b"", b""),
+            (b"f", b"Zg=="),
+            (b"fo", b"Zm8="),
+            (b"foo", b"Zm9v"),
+            (b"foob", b"Zm9vYg=="),
+            (b"fooba", b"Zm9vYmE="),
+            (b"foobar", b"Zm9vYmFy"),
+        ];
+
+        for &(plain, encoded) in vectors {
+            let mut buf = Vec::new();
+            Base64::Standard.encode_to(plain, &mut buf);
+            assert_eq!(buf, encoded);
+            assert_eq!(Base64::Standard.encoded_len(plain.len()), encoded.len());
+
+            let mut buf = Vec::new();
+            Base64::Standard.decode_to(encoded, &mut buf).unwrap();
+            assert_eq!(buf, plain);
+
+            // Padding is optional when decoding
+            let pad = encoded.iter().filter(|&&b| b == b'=').count();
+            let unpadded = &encoded[..encoded.len() - pad];
+
+            let mut buf = Vec::new();
+            Base64::StandardNoPad.encode_to(plain, &mut buf);
+            assert_eq!(buf, unpadded);
+
+            let mut buf = Vec::new();
+            Base64::Standard.decode_to(unpadded, &mut buf).unwrap();
+            assert_eq!(buf, plain);
+        }
+    }
+
+    #[test]
+    fn base64_long_round_trip() {
+        let plain: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+
+        let encodings = [
+            Base64::Standard,
+            Base64::StandardNoPad,
+            Base64::UrlSafe,
+            Base64::UrlSafeNoPad,
+        ];
+
+        for &encoding in &encodings {
+            let mut encoded = Vec::new();
+            encoding.encode_to(&plain, &mut encoded);
+            assert_eq!(encoded.len(), encoding.encoded_len(plain.len()));
+
+            let mut decoded = Vec::new();
+            encoding.decode_to(&encoded, &mut decoded).unwrap();
+            assert_eq!(decoded, plain);
+        }
+    }
+
+    #[test]
+    fn base64_invalid() {
+        fn decode(encoding: Base64, src: &[u8]) -> Result<(), DecodeError> {
+            let mut buf = Vec::new();
+            let res = encoding.decode_to(src, &mut buf);
+            assert!(buf.is_empty());
+            res
+        }
+
+        fn invalid_byte(index: usize, byte: u8) -> Result<(), DecodeError> {
+            Err(DecodeError::InvalidByte { index: index, byte: byte })
+        }
+
+        assert_eq!(decode(Base64::Standard, b"Zg="), Err(DecodeError::InvalidLength));
+        assert_eq!(decode(Base64::Standard, b"Zm9=v"), invalid_byte(3, b'='));
+        assert_eq!(decode(Base64::Standard, b"Zm9vY"), Err(DecodeError::InvalidLength));
+        assert_eq!(decode(Base64::Standard, b"Zh=="), invalid_byte(1, b'h'));
+        assert_eq!(decode(Base64::Standard, b"Zg==="), Err(DecodeError::InvalidLength));
+        assert_eq!(decode(Base64::Standard, b"Z==="), invalid_byte(1, b'='));
+        assert_eq!(decode(Base64::Standard, b"-_8="), invalid_byte(0, b'-'));
+        assert_eq!(decode(Base64::UrlSafe, b"+/8="), invalid_byte(0, b'+'));
+    }
+
+    #[test]
+    fn from_base64_capacity() {
+        for len in 0..20 {
+            let plain: Vec<u8> = (0..len).collect();
+
+            for &encoding in &[Base64::Standard, Base64::StandardNoPad] {
+                let mut encoded = Vec::new();
+                encoding.encode_to(&plain, &mut encoded);
+
+                assert_eq!(Bytes::from_base64(&encoded, encoding).unwrap(), plain);
+            }
+        }
+    }
+
+    #[test]
+    fn hex_round_trip() {
+        let plain: Vec<u8> = (0..=255).collect();
+
+        let mut encoded = Vec::new();
+        Hex::Upper.encode_to(&plain, &mut encoded);
+        assert_eq!(&encoded[..6], b"000102");
+        assert_eq!(&encoded[encoded.len() - 4..], b"FEFF");
+
+        let mut decoded = Vec::new();
+        Hex::Lower.decode_to(&encoded, &mut decoded).unwrap();
+        assert_eq!(decoded, plain);
+    }
+}
@@ -67,25 +67,64 @@
 //! argument to `Read::read` and `Write::write`. `Read` and `Write` may then
 //! perform a syscall, which has the potential of failing. Operations on `Buf`
 //! and `BufMut` are infallible.
+//!
+//! # `no_std` support
+//!
+//! The crate only requires `core` and `alloc`. Everything that needs `std`,
+//! such as the `std::io` adapters, vectored I/O and [`BytesPool`], is gated
+//! behind the `std` feature, which is enabled by default. To use the crate
+//! without `std`, disable the default features:
+//!
+//! ```toml
+//! [dependencies]
+//! bytes = { version = "0.5", default-features = false }
+//! ```
 
 #![deny(warnings, missing_docs, missing_debug_implementations, rust_2018_idioms)]
 #![doc(html_root_url = "https://docs.rs/bytes/0.5.0")]
+#![no_std]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+extern crate std;
 
 pub mod buf;
 pub use crate::buf::{
     Buf,
     BufMut,
     IntoBuf,
+    TryGetError,
 };
 
+mod byte_str;
+pub use crate::byte_str::{ByteStr, FromUtf8Error};
+
 mod bytes;
 mod debug;
-pub use crate::bytes::{Bytes, BytesMut};
+pub mod encoding;
+mod offset;
+pub use crate::offset::BoundsError;
+mod split;
+pub use crate::bytes::{Bytes, BytesMut, LenSlot, TryReserveError, WeakBytes};
+pub use crate::split::{Chunks, ChunksExact, IntoChunks, Lines, RChunks, Split};
+
+#[cfg(feature = "std")]
+mod pool;
+#[cfg(feature = "std")]
+pub use crate::pool::{BytesPool, PoolStats};
+
+mod secret;
+pub use crate::secret::{SecretBytes, SecretBytesMut};
 
 // Optional Serde support
 #[cfg(feature = "serde")]
-mod serde;
+pub mod serde;
 
 // Optional `Either` support
 #[cfg(feature = "either")]
 mod either;
+
+// Optional memory-mapped file support
+#[cfg(feature = "mmap")]
+mod mmap;
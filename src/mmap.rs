@@ -0,0 +1,113 @@
+use crate::Bytes;
+
+use memmap::MmapOptions;
+use std::fs::File;
+use std::io;
+use std::ops::Range;
+
+impl Bytes {
+    /// Creates a new `Bytes` backed by a read-only memory map of `file`.
+    ///
+    /// The file contents are not read up front. Pages are loaded by the
+    /// operating system as they are accessed. `clone`, `slice`, `split_to` and
+    /// `split_off` return handles into the same mapping, which is unmapped once
+    /// the last handle is dropped.
+    ///
+    /// Mapping an empty file returns an empty `Bytes`.
+    ///
+    /// # Safety
+    ///
+    /// The mapped memory is exposed as an immutable `&[u8]`. The caller must
+    /// ensure that the file is neither modified nor truncated, by this or any
+    /// other process, for as long as any handle to the mapping is alive.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use bytes::Bytes;
+    /// use std::fs::File;
+    ///
+    /// let file = File::open("ledger.bin").unwrap();
+    /// let ledger = unsafe { Bytes::map_file(&file).unwrap() };
+    ///
+    /// let header = ledger.slice(..64);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file metadata cannot be read or if the file
+    /// cannot be mapped.
+    pub unsafe fn map_file(file: &File) -> io::Result<Bytes> {
+        let len = file.metadata()?.len();
+        Bytes::map_range(file, 0..len)
+    }
+
+    /// Creates a new `Bytes` backed by a read-only memory map of the given
+    /// byte range of `file`.
+    ///
+    /// `range.start` does not need to be aligned to a page boundary. Handles
+    /// created from the returned `Bytes` share the mapping, which is unmapped
+    /// once the last handle is dropped.
+    ///
+    /// Mapping an empty range returns an empty `Bytes`.
+    ///
+    /// # Safety
+    ///
+    /// The mapped memory is exposed as an immutable `&[u8]`. The caller must
+    /// ensure that the mapped range of the file is neither modified nor
+    /// truncated, by this or any other process, for as long as any handle to
+    /// the mapping is alive.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use bytes::Bytes;
+    /// use std::fs::File;
+    ///
+    /// let file = File::open("ledger.bin").unwrap();
+    /// let segment = unsafe { Bytes::map_range(&file, 4096..8192).unwrap() };
+    ///
+    /// assert_eq!(segment.len(), 4096);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `range.start > range.end`, if `range.end` is past
+    /// the end of the file, if the range does not fit in the address space or
+    /// if the file cannot be mapped.
+    pub unsafe fn map_range(file: &File, range: Range<u64>) -> io::Result<Bytes> {
+        if range.start > range.end {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "mapped range start is greater than its end"));
+        }
+
+        // Pages of the mapping past the end of the file cannot be read, and
+        // accessing them raises `SIGBUS`.
+        if range.end > file.metadata()?.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "mapped range extends past the end of the file"));
+        }
+
+        let len = range.end - range.start;
+
+        if len > usize::max_value() as u64 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "mapped range does not fit in the address space"));
+        }
+
+        if len == 0 {
+            // Zero length mappings are rejected by the operating system.
+            return Ok(Bytes::new());
+        }
+
+        let map = MmapOptions::new()
+            .offset(range.start)
+            .len(len as usize)
+            .map(file)?;
+
+        Ok(Bytes::from_owner(map))
+    }
+}
@@ -0,0 +1,220 @@
+use crate::{Bytes, BytesMut};
+
+use core::{fmt, mem};
+
+// Random access reads and writes at a byte offset. Unlike the `Buf` and
+// `BufMut` methods, these never move the cursor.
+
+/// The error returned by the `try_get_*_at` and `try_set_*_at` methods of
+/// `Bytes` and `BytesMut` when the accessed range is out of bounds.
+///
+/// # Examples
+///
+/// ```
+/// use bytes::{Bytes, BoundsError};
+///
+/// let buf = Bytes::from(&b"\x00\x01\x02"[..]);
+/// let err = buf.try_get_u16_at(2).unwrap_err();
+///
+/// assert_eq!(err, BoundsError { offset: 2, size: 2, len: 3 });
+/// assert_eq!(err.to_string(), "range 2..4 is out of bounds for length 3");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundsError {
+    /// The offset of the first byte that was accessed.
+    pub offset: usize,
+
+    /// The number of bytes that were accessed.
+    pub size: usize,
+
+    /// The length of the buffer.
+    pub len: usize,
+}
+
+impl fmt::Display for BoundsError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            fmt,
+            "range {}..{} is out of bounds for length {}",
+            self.offset,
+            self.offset.saturating_add(self.size),
+            self.len
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BoundsError {}
+
+macro_rules! get_at_impl {
+    ($($get:ident, $try_get:ident, $typ:tt::$conv:ident, $desc:expr;)*) => {
+        $(
+            #[doc = concat!("Gets ", $desc, " at `offset`, without advancing.")]
+            ///
+            /// # Examples
+            ///
+            /// ```
+            /// use bytes::Bytes;
+            ///
+            /// let buf = Bytes::from(&[1; 32][..]);
+            #[doc = concat!("let n = ", stringify!($typ), "::", stringify!($conv), "([1; std::mem::size_of::<", stringify!($typ), ">()]);")]
+            #[doc = concat!("assert_eq!(buf.", stringify!($get), "(8), n);")]
+            /// ```
+            ///
+            /// # Panics
+            ///
+            /// This function panics if there is not enough data after `offset`.
+            #[inline]
+            pub fn $get(&self, offset: usize) -> $typ {
+                match self.$try_get(offset) {
+                    Ok(n) => n,
+                    Err(err) => panic!("{}", err),
+                }
+            }
+
+            #[doc = concat!("Gets ", $desc, " at `offset`, without advancing.")]
+            ///
+            /// Returns an error if there is not enough data after `offset`.
+            ///
+            /// # Examples
+            ///
+            /// ```
+            /// use bytes::Bytes;
+            ///
+            /// let buf = Bytes::from(&[1; 32][..]);
+            #[doc = concat!("assert!(buf.", stringify!($try_get), "(8).is_ok());")]
+            #[doc = concat!("assert!(buf.", stringify!($try_get), "(32).is_err());")]
+            /// ```
+            #[inline]
+            pub fn $try_get(&self, offset: usize) -> Result<$typ, BoundsError> {
+                const SIZE: usize = mem::size_of::<$typ>();
+
+                let src = offset.checked_add(SIZE)
+                    .and_then(|end| self.get(offset..end));
+
+                match src {
+                    Some(src) => {
+                        let mut buf = [0; SIZE];
+                        buf.copy_from_slice(src);
+                        Ok($typ::$conv(buf))
+                    }
+                    None => Err(BoundsError {
+                        offset: offset,
+                        size: SIZE,
+                        len: self.len(),
+                    }),
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! set_at_impl {
+    ($($set:ident, $try_set:ident, $get:ident, $typ:tt::$conv:ident, $desc:expr;)*) => {
+        $(
+            #[doc = concat!("Writes ", $desc, " at `offset`, overwriting the existing data.")]
+            ///
+            /// The length of `self` is not changed.
+            ///
+            /// # Examples
+            ///
+            /// ```
+            /// use bytes::BytesMut;
+            ///
+            /// let mut buf = BytesMut::from(&[0; 32][..]);
+            #[doc = concat!("buf.", stringify!($set), "(8, 1 as ", stringify!($typ), ");")]
+            #[doc = concat!("assert_eq!(buf.", stringify!($get), "(8), 1 as ", stringify!($typ), ");")]
+            /// assert_eq!(buf.len(), 32);
+            /// ```
+            ///
+            /// # Panics
+            ///
+            /// This function panics if there is not enough room after `offset`.
+            #[inline]
+            pub fn $set(&mut self, offset: usize, n: $typ) {
+                if let Err(err) = self.$try_set(offset, n) {
+                    panic!("{}", err);
+                }
+            }
+
+            #[doc = concat!("Writes ", $desc, " at `offset`, overwriting the existing data.")]
+            ///
+            /// Returns an error, without modifying `self`, if there is not enough
+            /// room after `offset`.
+            #[inline]
+            pub fn $try_set(&mut self, offset: usize, n: $typ) -> Result<(), BoundsError> {
+                const SIZE: usize = mem::size_of::<$typ>();
+
+                let len = self.len();
+                let dst = offset.checked_add(SIZE)
+                    .and_then(move |end| self.get_mut(offset..end));
+
+                match dst {
+                    Some(dst) => {
+                        dst.copy_from_slice(&n.$conv());
+                        Ok(())
+                    }
+                    None => Err(BoundsError {
+                        offset: offset,
+                        size: SIZE,
+                        len: len,
+                    }),
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! get_at_methods {
+    () => {
+        get_at_impl! {
+            get_u8_at, try_get_u8_at, u8::from_be_bytes, "an unsigned 8 bit integer";
+            get_i8_at, try_get_i8_at, i8::from_be_bytes, "a signed 8 bit integer";
+            get_u16_at, try_get_u16_at, u16::from_be_bytes, "an unsigned 16 bit integer in big-endian byte order";
+            get_u16_le_at, try_get_u16_le_at, u16::from_le_bytes, "an unsigned 16 bit integer in little-endian byte order";
+            get_i16_at, try_get_i16_at, i16::from_be_bytes, "a signed 16 bit integer in big-endian byte order";
+            get_i16_le_at, try_get_i16_le_at, i16::from_le_bytes, "a signed 16 bit integer in little-endian byte order";
+            get_u32_at, try_get_u32_at, u32::from_be_bytes, "an unsigned 32 bit integer in big-endian byte order";
+            get_u32_le_at, try_get_u32_le_at, u32::from_le_bytes, "an unsigned 32 bit integer in little-endian byte order";
+            get_i32_at, try_get_i32_at, i32::from_be_bytes, "a signed 32 bit integer in big-endian byte order";
+            get_i32_le_at, try_get_i32_le_at, i32::from_le_bytes, "a signed 32 bit integer in little-endian byte order";
+            get_u64_at, try_get_u64_at, u64::from_be_bytes, "an unsigned 64 bit integer in big-endian byte order";
+            get_u64_le_at, try_get_u64_le_at, u64::from_le_bytes, "an unsigned 64 bit integer in little-endian byte order";
+            get_i64_at, try_get_i64_at, i64::from_be_bytes, "a signed 64 bit integer in big-endian byte order";
+            get_i64_le_at, try_get_i64_le_at, i64::from_le_bytes, "a signed 64 bit integer in little-endian byte order";
+            get_f32_at, try_get_f32_at, f32::from_be_bytes, "an IEEE754 single-precision floating point number in big-endian byte order";
+            get_f32_le_at, try_get_f32_le_at, f32::from_le_bytes, "an IEEE754 single-precision floating point number in little-endian byte order";
+            get_f64_at, try_get_f64_at, f64::from_be_bytes, "an IEEE754 double-precision floating point number in big-endian byte order";
+            get_f64_le_at, try_get_f64_le_at, f64::from_le_bytes, "an IEEE754 double-precision floating point number in little-endian byte order";
+        }
+    };
+}
+
+impl Bytes {
+    get_at_methods!();
+}
+
+impl BytesMut {
+    get_at_methods!();
+
+    set_at_impl! {
+        set_u8_at, try_set_u8_at, get_u8_at, u8::to_be_bytes, "an unsigned 8 bit integer";
+        set_i8_at, try_set_i8_at, get_i8_at, i8::to_be_bytes, "a signed 8 bit integer";
+        set_u16_at, try_set_u16_at, get_u16_at, u16::to_be_bytes, "an unsigned 16 bit integer in big-endian byte order";
+        set_u16_le_at, try_set_u16_le_at, get_u16_le_at, u16::to_le_bytes, "an unsigned 16 bit integer in little-endian byte order";
+        set_i16_at, try_set_i16_at, get_i16_at, i16::to_be_bytes, "a signed 16 bit integer in big-endian byte order";
+        set_i16_le_at, try_set_i16_le_at, get_i16_le_at, i16::to_le_bytes, "a signed 16 bit integer in little-endian byte order";
+        set_u32_at, try_set_u32_at, get_u32_at, u32::to_be_bytes, "an unsigned 32 bit integer in big-endian byte order";
+        set_u32_le_at, try_set_u32_le_at, get_u32_le_at, u32::to_le_bytes, "an unsigned 32 bit integer in little-endian byte order";
+        set_i32_at, try_set_i32_at, get_i32_at, i32::to_be_bytes, "a signed 32 bit integer in big-endian byte order";
+        set_i32_le_at, try_set_i32_le_at, get_i32_le_at, i32::to_le_bytes, "a signed 32 bit integer in little-endian byte order";
+        set_u64_at, try_set_u64_at, get_u64_at, u64::to_be_bytes, "an unsigned 64 bit integer in big-endian byte order";
+        set_u64_le_at, try_set_u64_le_at, get_u64_le_at, u64::to_le_bytes, "an unsigned 64 bit integer in little-endian byte order";
+        set_i64_at, try_set_i64_at, get_i64_at, i64::to_be_bytes, "a signed 64 bit integer in big-endian byte order";
+        set_i64_le_at, try_set_i64_le_at, get_i64_le_at, i64::to_le_bytes, "a signed 64 bit integer in little-endian byte order";
+        set_f32_at, try_set_f32_at, get_f32_at, f32::to_be_bytes, "an IEEE754 single-precision floating point number in big-endian byte order";
+        set_f32_le_at, try_set_f32_le_at, get_f32_le_at, f32::to_le_bytes, "an IEEE754 single-precision floating point number in little-endian byte order";
+        set_f64_at, try_set_f64_at, get_f64_at, f64::to_be_bytes, "an IEEE754 double-precision floating point number in big-endian byte order";
+        set_f64_le_at, try_set_f64_le_at, get_f64_le_at, f64::to_le_bytes, "an IEEE754 double-precision floating point number in little-endian byte order";
+    }
+}
@@ -0,0 +1,248 @@
+use crate::BytesMut;
+use crate::bytes::Recycle;
+
+use core::{fmt, mem};
+
+use alloc::{boxed::Box, vec::Vec};
+
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// A pool of reusable buffer allocations.
+///
+/// `BytesPool` hands out `BytesMut` values whose capacity is rounded up to one
+/// of a fixed set of size classes. Once the last `Bytes` or `BytesMut` handle
+/// referencing a pooled allocation is dropped, the allocation is returned to
+/// the pool instead of being freed, and is handed out again by a later call to
+/// [`get`].
+///
+/// The pool never retains more than a configurable number of bytes. Buffers
+/// that are returned while the pool is full are freed.
+///
+/// `BytesPool` is a handle to shared state, cloning it returns a new handle to
+/// the same pool. Buffers keep the pool alive until they are dropped.
+///
+/// [`get`]: #method.get
+///
+/// # Examples
+///
+/// ```
+/// use bytes::{BytesPool, BufMut};
+///
+/// let pool = BytesPool::new(&[512, 4096], 64 * 1024);
+///
+/// let mut buf = pool.get(1000);
+/// assert_eq!(buf.capacity(), 4096);
+///
+/// buf.put(&b"hello world"[..]);
+/// let frozen = buf.freeze();
+/// drop(frozen);
+///
+/// // The allocation was returned to the pool and is reused.
+/// let buf = pool.get(4096);
+/// assert!(buf.is_empty());
+///
+/// let stats = pool.stats();
+/// assert_eq!(stats.hits, 1);
+/// assert_eq!(stats.misses, 1);
+/// ```
+#[derive(Clone)]
+pub struct BytesPool {
+    inner: Arc<Pool>,
+}
+
+/// A snapshot of the statistics of a [`BytesPool`].
+///
+/// [`BytesPool`]: struct.BytesPool.html
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// Number of buffers handed out using a retained allocation.
+    pub hits: usize,
+    /// Number of buffers handed out using a new allocation.
+    pub misses: usize,
+    /// Number of allocations returned to and retained by the pool.
+    pub returned: usize,
+    /// Number of allocations freed because the pool was full.
+    pub discarded: usize,
+    /// Total capacity, in bytes, of the allocations currently retained.
+    pub retained_bytes: usize,
+}
+
+struct Pool {
+    size_classes: Box<[usize]>,
+    max_retained: usize,
+    state: Mutex<State>,
+}
+
+struct State {
+    // One free list per size class.
+    free: Vec<Vec<Vec<u8>>>,
+    stats: PoolStats,
+}
+
+impl BytesPool {
+    /// Creates a new pool with the given size classes that retains at most
+    /// `max_retained` bytes worth of unused allocations.
+    ///
+    /// The size classes do not need to be sorted. Zero sized and duplicate
+    /// classes are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::BytesPool;
+    ///
+    /// let pool = BytesPool::new(&[4096, 1024], 1024 * 1024);
+    /// assert_eq!(pool.size_classes(), &[1024, 4096]);
+    /// ```
+    pub fn new(size_classes: &[usize], max_retained: usize) -> BytesPool {
+        let mut size_classes: Vec<usize> = size_classes.iter()
+            .cloned()
+            .filter(|&size| size > 0)
+            .collect();
+
+        size_classes.sort();
+        size_classes.dedup();
+
+        let free = size_classes.iter().map(|_| Vec::new()).collect();
+
+        BytesPool {
+            inner: Arc::new(Pool {
+                size_classes: size_classes.into_boxed_slice(),
+                max_retained: max_retained,
+                state: Mutex::new(State {
+                    free: free,
+                    stats: PoolStats::default(),
+                }),
+            }),
+        }
+    }
+
+    /// Returns an empty `BytesMut` with a capacity of at least `capacity`.
+    ///
+    /// The capacity is rounded up to the smallest size class that fits. If
+    /// `capacity` is larger than every size class, a regular `BytesMut` is
+    /// allocated instead, which is counted as a miss and is not returned to
+    /// the pool.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::BytesPool;
+    ///
+    /// let pool = BytesPool::new(&[1024], 1024 * 1024);
+    ///
+    /// assert_eq!(pool.get(10).capacity(), 1024);
+    /// assert_eq!(pool.get(2000).capacity(), 2000);
+    /// ```
+    pub fn get(&self, capacity: usize) -> BytesMut {
+        let class = match self.inner.size_classes.iter().position(|&size| size >= capacity) {
+            Some(class) => class,
+            None => {
+                self.inner.lock().stats.misses += 1;
+                return BytesMut::with_capacity(capacity);
+            }
+        };
+
+        let vec = {
+            let mut state = self.inner.lock();
+
+            match state.free[class].pop() {
+                Some(vec) => {
+                    state.stats.hits += 1;
+                    state.stats.retained_bytes -= vec.capacity();
+                    Some(vec)
+                }
+                None => {
+                    state.stats.misses += 1;
+                    None
+                }
+            }
+        };
+
+        // Allocate outside of the lock.
+        let vec = vec.unwrap_or_else(|| Vec::with_capacity(self.inner.size_classes[class]));
+
+        BytesMut::from_recycled_vec(vec, self.inner.clone())
+    }
+
+    /// Returns the size classes of the pool, in increasing order.
+    pub fn size_classes(&self) -> &[usize] {
+        &self.inner.size_classes
+    }
+
+    /// Returns the maximum number of bytes retained by the pool.
+    pub fn max_retained(&self) -> usize {
+        self.inner.max_retained
+    }
+
+    /// Returns a snapshot of the pool statistics.
+    pub fn stats(&self) -> PoolStats {
+        self.inner.lock().stats
+    }
+
+    /// Frees all allocations currently retained by the pool.
+    ///
+    /// Buffers that are still in use are returned to the pool as usual.
+    pub fn clear(&self) {
+        let free = {
+            let mut state = self.inner.lock();
+            state.stats.retained_bytes = 0;
+
+            let classes = state.free.len();
+            mem::replace(&mut state.free, (0..classes).map(|_| Vec::new()).collect())
+        };
+
+        // Free outside of the lock.
+        drop(free);
+    }
+}
+
+impl fmt::Debug for BytesPool {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("BytesPool")
+            .field("size_classes", &self.inner.size_classes)
+            .field("max_retained", &self.inner.max_retained)
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+impl Pool {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // The state is always left consistent, so a panic while holding the
+        // lock does not need to be propagated. This is also called from `drop`
+        // paths, which must not panic.
+        match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+impl Recycle for Arc<Pool> {
+    fn recycle(&self, mut vec: Vec<u8>) {
+        vec.clear();
+
+        let cap = vec.capacity();
+
+        // Use the largest size class the allocation can still serve.
+        let class = self.size_classes.iter().rposition(|&size| size <= cap);
+
+        let mut state = self.lock();
+
+        match class {
+            Some(class) if state.stats.retained_bytes + cap <= self.max_retained => {
+                state.stats.returned += 1;
+                state.stats.retained_bytes += cap;
+                state.free[class].push(vec);
+            }
+            _ => {
+                state.stats.discarded += 1;
+
+                // Free outside of the lock.
+                drop(state);
+                drop(vec);
+            }
+        }
+    }
+}
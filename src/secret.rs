@@ -0,0 +1,491 @@
+use crate::{Buf, BufMut, Bytes, BytesMut};
+use crate::buf::UninitSlice;
+use crate::bytes::Recycle;
+
+use core::{cmp, fmt, ptr};
+use core::ops::{Deref, DerefMut, RangeBounds};
+use core::sync::atomic::{self, Ordering};
+
+use alloc::vec::Vec;
+
+/// A unique reference to a contiguous slice of sensitive memory.
+///
+/// `SecretBytesMut` works like [`BytesMut`], except that every allocation the
+/// data is ever stored in is overwritten with zeros before it is freed. This
+/// includes allocations that are abandoned when the buffer grows, and
+/// allocations shared with handles created by splitting the buffer, which are
+/// zeroed once the last handle referencing them is dropped.
+///
+/// Unlike `BytesMut`, small buffers are never stored inline, so the data is
+/// never copied into the handle itself.
+///
+/// The `Debug` implementation does not print the contents of the buffer.
+/// `PartialEq` is deliberately not implemented, as comparisons of secrets
+/// usually have to be done in constant time.
+///
+/// [`BytesMut`]: struct.BytesMut.html
+///
+/// # Examples
+///
+/// ```
+/// use bytes::{BufMut, SecretBytesMut};
+///
+/// let mut key = SecretBytesMut::with_capacity(32);
+/// key.put(&[0x2a; 32][..]);
+///
+/// assert_eq!(format!("{:?}", key), "SecretBytesMut([REDACTED])");
+///
+/// let key = key.freeze();
+/// assert_eq!(&key[..], &[0x2a; 32][..]);
+/// ```
+pub struct SecretBytesMut {
+    inner: BytesMut,
+}
+
+/// A cheaply cloneable and sliceable chunk of sensitive memory.
+///
+/// `SecretBytes` is the immutable counterpart of [`SecretBytesMut`]. The
+/// memory is overwritten with zeros once the last handle referencing it is
+/// dropped.
+///
+/// [`SecretBytesMut`]: struct.SecretBytesMut.html
+#[derive(Clone)]
+pub struct SecretBytes {
+    inner: Bytes,
+}
+
+// Zeroes the vector backing a secret buffer before it is freed.
+struct Zeroize;
+
+impl Recycle for Zeroize {
+    fn recycle(&self, mut vec: Vec<u8>) {
+        zeroize(&mut vec);
+    }
+}
+
+// Overwrites the whole capacity of `vec` with zeros.
+fn zeroize(vec: &mut Vec<u8>) {
+    let ptr = vec.as_mut_ptr();
+
+    for i in 0..vec.capacity() {
+        // Volatile writes are not elided, even though the memory is about to
+        // be freed.
+        unsafe { ptr::write_volatile(ptr.add(i), 0); }
+    }
+
+    // Prevent the writes from being reordered past the deallocation.
+    atomic::compiler_fence(Ordering::SeqCst);
+}
+
+/*
+ *
+ * ===== SecretBytesMut =====
+ *
+ */
+
+impl SecretBytesMut {
+    /// Creates a new, empty `SecretBytesMut`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::SecretBytesMut;
+    ///
+    /// let buf = SecretBytesMut::new();
+    /// assert!(buf.is_empty());
+    /// ```
+    pub fn new() -> SecretBytesMut {
+        SecretBytesMut::with_capacity(0)
+    }
+
+    /// Creates a new `SecretBytesMut` with the specified capacity.
+    ///
+    /// Unlike `BytesMut::with_capacity`, the buffer is always allocated on the
+    /// heap, even if `capacity` is small.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::SecretBytesMut;
+    ///
+    /// let buf = SecretBytesMut::with_capacity(16);
+    /// assert_eq!(buf.capacity(), 16);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> SecretBytesMut {
+        SecretBytesMut::from(Vec::with_capacity(capacity))
+    }
+
+    /// Returns the number of bytes contained in this `SecretBytesMut`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns true if the `SecretBytesMut` has a length of 0.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns the number of bytes the `SecretBytesMut` can hold without
+    /// reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    /// Converts `self` into an immutable `SecretBytes`.
+    #[inline]
+    pub fn freeze(self) -> SecretBytes {
+        SecretBytes { inner: self.inner.freeze() }
+    }
+
+    /// Splits the buffer into two at the given index.
+    ///
+    /// Afterwards `self` contains elements `[0, at)`, and the returned
+    /// `SecretBytesMut` contains elements `[at, capacity)`. Both handles keep
+    /// referencing the same allocation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > capacity`.
+    pub fn split_off(&mut self, at: usize) -> SecretBytesMut {
+        SecretBytesMut { inner: self.inner.split_off(at) }
+    }
+
+    /// Removes the bytes from the current view, returning them in a new
+    /// `SecretBytesMut` handle.
+    ///
+    /// Afterwards, `self` will be empty, but will retain any additional
+    /// capacity that it had before the operation.
+    pub fn split(&mut self) -> SecretBytesMut {
+        SecretBytesMut { inner: self.inner.split() }
+    }
+
+    /// Splits the buffer into two at the given index.
+    ///
+    /// Afterwards `self` contains elements `[at, len)`, and the returned
+    /// `SecretBytesMut` contains elements `[0, at)`. Both handles keep
+    /// referencing the same allocation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    pub fn split_to(&mut self, at: usize) -> SecretBytesMut {
+        SecretBytesMut { inner: self.inner.split_to(at) }
+    }
+
+    /// Shortens the buffer, keeping the first `len` bytes and dropping the
+    /// rest.
+    ///
+    /// The dropped bytes are not zeroed until the allocation is freed.
+    pub fn truncate(&mut self, len: usize) {
+        self.inner.truncate(len);
+    }
+
+    /// Clears the buffer, removing all data.
+    ///
+    /// The dropped bytes are not zeroed until the allocation is freed.
+    pub fn clear(&mut self) {
+        self.inner.clear();
+    }
+
+    /// Reserves capacity for at least `additional` more bytes to be inserted
+    /// into the given `SecretBytesMut`.
+    ///
+    /// If the current allocation is too small, the data is copied into a new
+    /// allocation. The old allocation is zeroed once no other handle
+    /// references it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::SecretBytesMut;
+    ///
+    /// let mut buf = SecretBytesMut::from(&b"hello"[..]);
+    /// buf.reserve(64);
+    ///
+    /// assert!(buf.capacity() >= 69);
+    /// assert_eq!(&buf[..], b"hello");
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        let len = self.len();
+        let cap = self.capacity();
+
+        if cap - len >= additional {
+            return;
+        }
+
+        let new_cap = len.checked_add(additional).expect("capacity overflow");
+        let new_cap = cmp::max(new_cap, cap.saturating_mul(2));
+
+        let mut new = SecretBytesMut::with_capacity(new_cap);
+        new.inner.put_slice(&self.inner);
+
+        // Dropping the old handle releases the old allocation.
+        *self = new;
+    }
+
+    /// Appends given bytes to this `SecretBytesMut`.
+    ///
+    /// If this `SecretBytesMut` object does not have enough capacity, it is
+    /// resized first.
+    pub fn extend_from_slice(&mut self, extend: &[u8]) {
+        self.reserve(extend.len());
+        self.inner.put_slice(extend);
+    }
+
+    /// Absorbs a `SecretBytesMut` that was previously split off.
+    ///
+    /// If the two buffers are contiguous, this is an `O(1)` operation.
+    /// Otherwise the bytes of `other` are copied.
+    pub fn unsplit(&mut self, other: SecretBytesMut) {
+        if self.is_empty() {
+            *self = other;
+            return;
+        }
+
+        // `BytesMut::unsplit` would copy into a regular allocation.
+        if let Err(other) = self.inner.try_unsplit(other.inner) {
+            self.extend_from_slice(&other);
+        }
+    }
+}
+
+impl BufMut for SecretBytesMut {
+    #[inline]
+    fn remaining_mut(&self) -> usize {
+        usize::MAX - self.len()
+    }
+
+    #[inline]
+    unsafe fn advance_mut(&mut self, cnt: usize) {
+        // This call will panic if `cnt` is too big
+        self.inner.advance_mut(cnt);
+    }
+
+    #[inline]
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        if self.capacity() == self.len() {
+            self.reserve(64);
+        }
+
+        self.inner.chunk_mut()
+    }
+
+    #[inline]
+    fn put_slice(&mut self, src: &[u8]) {
+        self.extend_from_slice(src);
+    }
+}
+
+impl Default for SecretBytesMut {
+    #[inline]
+    fn default() -> SecretBytesMut {
+        SecretBytesMut::new()
+    }
+}
+
+impl Clone for SecretBytesMut {
+    fn clone(&self) -> SecretBytesMut {
+        SecretBytesMut::from(&self[..])
+    }
+}
+
+impl Deref for SecretBytesMut {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        &self.inner
+    }
+}
+
+impl DerefMut for SecretBytesMut {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.inner
+    }
+}
+
+impl AsRef<[u8]> for SecretBytesMut {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.inner
+    }
+}
+
+impl AsMut<[u8]> for SecretBytesMut {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.inner
+    }
+}
+
+impl From<Vec<u8>> for SecretBytesMut {
+    /// Takes ownership of `src`. Its allocation will be zeroed once it is no
+    /// longer referenced.
+    fn from(src: Vec<u8>) -> SecretBytesMut {
+        SecretBytesMut {
+            inner: BytesMut::from_recycled_vec(src, Zeroize),
+        }
+    }
+}
+
+impl<'a> From<&'a [u8]> for SecretBytesMut {
+    fn from(src: &'a [u8]) -> SecretBytesMut {
+        let mut buf = SecretBytesMut::with_capacity(src.len());
+        buf.inner.put_slice(src);
+        buf
+    }
+}
+
+impl fmt::Debug for SecretBytesMut {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("SecretBytesMut([REDACTED])")
+    }
+}
+
+/*
+ *
+ * ===== SecretBytes =====
+ *
+ */
+
+impl SecretBytes {
+    /// Returns the number of bytes contained in this `SecretBytes`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns true if the `SecretBytes` has a length of 0.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns a slice of self for the provided range.
+    ///
+    /// Unlike `Bytes::slice`, the returned handle always references the
+    /// original allocation, the data is never copied.
+    ///
+    /// # Panics
+    ///
+    /// Requires that `begin <= end` and `end <= self.len()`, otherwise slicing
+    /// will panic.
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> SecretBytes {
+        use core::ops::Bound;
+
+        let len = self.len();
+
+        let begin = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n + 1,
+            Bound::Unbounded => 0,
+        };
+
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n + 1,
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => len,
+        };
+
+        assert!(begin <= end);
+        assert!(end <= len);
+
+        let mut ret = self.clone();
+        ret.inner.truncate(end);
+        ret.inner.advance(begin);
+        ret
+    }
+
+    /// Splits the bytes into two at the given index.
+    ///
+    /// Afterwards `self` contains elements `[0, at)`, and the returned
+    /// `SecretBytes` contains elements `[at, len)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    pub fn split_off(&mut self, at: usize) -> SecretBytes {
+        SecretBytes { inner: self.inner.split_off(at) }
+    }
+
+    /// Splits the bytes into two at the given index.
+    ///
+    /// Afterwards `self` contains elements `[at, len)`, and the returned
+    /// `SecretBytes` contains elements `[0, at)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    pub fn split_to(&mut self, at: usize) -> SecretBytes {
+        SecretBytes { inner: self.inner.split_to(at) }
+    }
+
+    /// Shortens the buffer, keeping the first `len` bytes and dropping the
+    /// rest.
+    pub fn truncate(&mut self, len: usize) {
+        self.inner.truncate(len);
+    }
+
+    /// Clears the buffer, removing all data.
+    pub fn clear(&mut self) {
+        self.inner.clear();
+    }
+}
+
+impl Deref for SecretBytes {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        &self.inner
+    }
+}
+
+impl AsRef<[u8]> for SecretBytes {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.inner
+    }
+}
+
+impl From<SecretBytesMut> for SecretBytes {
+    fn from(src: SecretBytesMut) -> SecretBytes {
+        src.freeze()
+    }
+}
+
+impl<'a> From<&'a [u8]> for SecretBytes {
+    fn from(src: &'a [u8]) -> SecretBytes {
+        SecretBytesMut::from(src).freeze()
+    }
+}
+
+impl fmt::Debug for SecretBytes {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("SecretBytes([REDACTED])")
+    }
+}
+
+#[test]
+fn test_zeroize() {
+    let mut vec = Vec::with_capacity(64);
+    vec.extend_from_slice(&[0xff; 48]);
+
+    unsafe {
+        // Fill the spare capacity as well.
+        ptr::write_bytes(vec.as_mut_ptr().add(48), 0xff, 16);
+    }
+
+    zeroize(&mut vec);
+
+    let all = unsafe { ::core::slice::from_raw_parts(vec.as_ptr(), 64) };
+    assert_eq!(all, &[0; 64][..]);
+}
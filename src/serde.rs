@@ -1,67 +1,231 @@
-use std::{cmp, fmt};
+//! Serde support.
+//!
+//! `Bytes` and `BytesMut` serialize differently depending on the format. For
+//! human-readable formats such as JSON or YAML, they are written as base64
+//! strings, and can be read back from a base64 string (standard or URL-safe
+//! alphabet, with or without padding) or a sequence of integers. For compact
+//! formats such as bincode, they are written as byte arrays.
+//!
+//! Hex strings are accepted as well, but only with a `0x` prefix and only if
+//! the string is not valid base64, since a string such as `"0xAA"` is both.
+//! Use the [`hex`] module for fields that are always written as hex.
+//!
+//! [`hex`]: hex/index.html
+//!
+//! The modules in here can be used with `#[serde(with = "...")]` to pick the
+//! string encoding explicitly. They also work for `Vec<u8>` fields, and like
+//! the default implementations, they keep byte arrays for compact formats.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use bytes::Bytes;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Block {
+//!     // Written as "0a1b..." in JSON
+//!     #[serde(with = "bytes::serde::hex")]
+//!     hash: Bytes,
+//!     // Written as "aGVsbG8=" in JSON
+//!     payload: Bytes,
+//! }
+//! ```
+
+use core::{cmp, fmt, str};
+
+use alloc::{string::String, vec::Vec};
 use serde::{Serialize, Serializer, Deserialize, Deserializer, de};
-use super::{Bytes, BytesMut};
+use super::{ByteStr, Bytes, BytesMut};
+use crate::encoding::{Base64, Hex};
 
-macro_rules! serde_impl {
-    ($ty:ident, $visitor_ty:ident) => (
-        impl Serialize for $ty {
-            #[inline]
-            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-                where S: Serializer
-            {
-                serializer.serialize_bytes(&self)
-            }
-        }
+// The string encoding used for human-readable formats.
+#[derive(Clone, Copy)]
+enum Encoding {
+    // Writes standard base64, reads either base64 alphabet or `0x`-prefixed
+    // hex.
+    Default,
+    // Writes lowercase hex, reads hex with an optional `0x` prefix.
+    Hex,
+    // Writes and reads the given base64 variant.
+    Base64(Base64),
+}
 
-        struct $visitor_ty;
+impl Encoding {
+    fn expecting(self) -> &'static str {
+        match self {
+            Encoding::Default => "a base64 string, 0x-prefixed hex string or byte array",
+            Encoding::Hex => "a hex string or byte array",
+            Encoding::Base64(_) => "a base64 string or byte array",
+        }
+    }
 
-        impl<'de> de::Visitor<'de> for $visitor_ty {
-            type Value = $ty;
+    fn decode(self, src: &str) -> Option<Vec<u8>> {
+        let mut dst = Vec::new();
 
-            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-                formatter.write_str("byte array")
+        let res = match self {
+            // Strings without any of `+/-_` are valid in both base64
+            // alphabets and decode the same way, so the order only matters
+            // for strings that neither can decode. Hex comes last, so the
+            // output of `Encoding::Default` always reads back as base64.
+            Encoding::Default => Base64::Standard.decode_to(src.as_bytes(), &mut dst)
+                .or_else(|_| Base64::UrlSafe.decode_to(src.as_bytes(), &mut dst))
+                .or_else(|err| match strip_hex_prefix(src) {
+                    Some(hex) => Hex::Lower.decode_to(hex.as_bytes(), &mut dst),
+                    None => Err(err),
+                }),
+            Encoding::Hex => {
+                let hex = strip_hex_prefix(src).unwrap_or(src);
+                Hex::Lower.decode_to(hex.as_bytes(), &mut dst)
             }
+            Encoding::Base64(encoding) => encoding.decode_to(src.as_bytes(), &mut dst),
+        };
 
-            #[inline]
-            fn visit_seq<V>(self, mut seq: V) -> Result<Self::Value, V::Error>
-                where V: de::SeqAccess<'de>
-            {
-                let len = cmp::min(seq.size_hint().unwrap_or(0), 4096);
-                let mut values = Vec::with_capacity(len);
+        res.ok().map(|_| dst)
+    }
+}
+
+fn strip_hex_prefix(src: &str) -> Option<&str> {
+    if src.starts_with("0x") || src.starts_with("0X") {
+        Some(&src[2..])
+    } else {
+        None
+    }
+}
+
+// Formats bytes with a string encoding, so they can be written with
+// `Serializer::collect_str` without allocating.
+struct Encoded<'a> {
+    data: &'a [u8],
+    encoding: Encoding,
+}
+
+impl fmt::Display for Encoded<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Both chunk sizes encode to exactly 64 bytes. Base64 chunks are a
+        // multiple of 3 bytes, so only the last one can be padded.
+        let (chunk_len, encoding) = match self.encoding {
+            Encoding::Hex => (32, None),
+            Encoding::Default => (48, Some(Base64::Standard)),
+            Encoding::Base64(encoding) => (48, Some(encoding)),
+        };
+
+        let mut staging = [0; 64];
+
+        for chunk in self.data.chunks(chunk_len) {
+            let len = {
+                let mut dst = &mut staging[..];
 
-                while let Some(value) = seq.next_element()? {
-                    values.push(value);
+                match encoding {
+                    Some(encoding) => encoding.encode_to(chunk, &mut dst),
+                    None => Hex::Lower.encode_to(chunk, &mut dst),
                 }
 
-                Ok(values.into())
-            }
+                64 - dst.len()
+            };
 
-            #[inline]
-            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
-                where E: de::Error
-            {
-                Ok($ty::from(v))
-            }
+            // Both encodings only produce ASCII.
+            fmt.write_str(unsafe { str::from_utf8_unchecked(&staging[..len]) })?;
+        }
 
-            #[inline]
-            fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
-                where E: de::Error
-            {
-                Ok($ty::from(v))
-            }
+        Ok(())
+    }
+}
 
-            #[inline]
-            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
-                where E: de::Error
-            {
-                Ok($ty::from(v))
-            }
+fn serialize<S>(data: &[u8], encoding: Encoding, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    if serializer.is_human_readable() {
+        serializer.collect_str(&Encoded { data: data, encoding: encoding })
+    } else {
+        serializer.serialize_bytes(data)
+    }
+}
+
+fn deserialize<'de, D>(encoding: Encoding, deserializer: D) -> Result<Vec<u8>, D::Error>
+    where D: Deserializer<'de>
+{
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_any(BytesVisitor { encoding: Some(encoding) })
+    } else {
+        deserializer.deserialize_byte_buf(BytesVisitor { encoding: None })
+    }
+}
+
+// Reads bytes from a byte array, a sequence of integers or a string. Strings
+// are decoded with `encoding`, or taken as raw bytes if it is `None`.
+struct BytesVisitor {
+    encoding: Option<Encoding>,
+}
+
+impl<'de> de::Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.encoding {
+            Some(encoding) => formatter.write_str(encoding.expecting()),
+            None => formatter.write_str("byte array"),
+        }
+    }
+
+    #[inline]
+    fn visit_seq<V>(self, mut seq: V) -> Result<Self::Value, V::Error>
+        where V: de::SeqAccess<'de>
+    {
+        let len = cmp::min(seq.size_hint().unwrap_or(0), 4096);
+        let mut values = Vec::with_capacity(len);
 
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+
+        Ok(values)
+    }
+
+    #[inline]
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+        where E: de::Error
+    {
+        Ok(v.to_vec())
+    }
+
+    #[inline]
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+        where E: de::Error
+    {
+        Ok(v)
+    }
+
+    #[inline]
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where E: de::Error
+    {
+        match self.encoding {
+            Some(encoding) => encoding.decode(v)
+                .ok_or_else(|| E::invalid_value(de::Unexpected::Str(v), &self)),
+            None => Ok(v.as_bytes().to_vec()),
+        }
+    }
+
+    #[inline]
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+        where E: de::Error
+    {
+        match self.encoding {
+            Some(_) => self.visit_str(&v),
+            None => Ok(v.into_bytes()),
+        }
+    }
+}
+
+macro_rules! serde_impl {
+    ($ty:ident) => (
+        impl Serialize for $ty {
             #[inline]
-            fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
-                where E: de::Error
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where S: Serializer
             {
-                Ok($ty::from(v))
+                serialize(self, Encoding::Default, serializer)
             }
         }
 
@@ -70,11 +234,120 @@ macro_rules! serde_impl {
             fn deserialize<D>(deserializer: D) -> Result<$ty, D::Error>
                 where D: Deserializer<'de>
             {
-                deserializer.deserialize_byte_buf($visitor_ty)
+                deserialize(Encoding::Default, deserializer).map($ty::from)
+            }
+        }
+    );
+}
+
+serde_impl!(Bytes);
+serde_impl!(BytesMut);
+
+macro_rules! serde_with_mod {
+    ($(#[$attr:meta])* $name:ident, $encoding:expr) => (
+        $(#[$attr])*
+        pub mod $name {
+            use super::{Encoding, Serializer, Deserializer, Vec};
+            #[allow(unused_imports)]
+            use crate::encoding::Base64;
+
+            /// Serializes `value` as a string for human-readable formats, and
+            /// as a byte array otherwise.
+            pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+                where T: AsRef<[u8]> + ?Sized,
+                      S: Serializer,
+            {
+                super::serialize(value.as_ref(), $encoding, serializer)
+            }
+
+            /// Deserializes a value written by `serialize`.
+            ///
+            /// Byte arrays and sequences of integers are accepted as well.
+            pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+                where T: From<Vec<u8>>,
+                      D: Deserializer<'de>,
+            {
+                super::deserialize($encoding, deserializer).map(T::from)
             }
         }
     );
 }
 
-serde_impl!(Bytes, BytesVisitor);
-serde_impl!(BytesMut, BytesMutVisitor);
+serde_with_mod! {
+    /// Encodes bytes as a lowercase hex string.
+    ///
+    /// A `0x` prefix and uppercase digits are accepted when deserializing.
+    hex, Encoding::Hex
+}
+
+serde_with_mod! {
+    /// Encodes bytes as a standard base64 string, with padding.
+    ///
+    /// Strings without padding are accepted when deserializing.
+    base64, Encoding::Base64(Base64::Standard)
+}
+
+serde_with_mod! {
+    /// Encodes bytes as a URL-safe base64 string, without padding.
+    ///
+    /// Strings with padding are accepted when deserializing.
+    base64_url, Encoding::Base64(Base64::UrlSafeNoPad)
+}
+
+impl Serialize for ByteStr {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        serializer.serialize_str(self)
+    }
+}
+
+struct ByteStrVisitor;
+
+impl<'de> de::Visitor<'de> for ByteStrVisitor {
+    type Value = ByteStr;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a string")
+    }
+
+    #[inline]
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where E: de::Error
+    {
+        Ok(ByteStr::from(v))
+    }
+
+    #[inline]
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+        where E: de::Error
+    {
+        Ok(ByteStr::from(v))
+    }
+
+    #[inline]
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+        where E: de::Error
+    {
+        ByteStr::from_utf8(Bytes::from(v))
+            .map_err(|_| E::invalid_value(de::Unexpected::Bytes(v), &self))
+    }
+
+    #[inline]
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+        where E: de::Error
+    {
+        ByteStr::from_utf8(Bytes::from(v))
+            .map_err(|err| E::invalid_value(de::Unexpected::Bytes(err.as_bytes()), &self))
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteStr {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<ByteStr, D::Error>
+        where D: Deserializer<'de>
+    {
+        deserializer.deserialize_string(ByteStrVisitor)
+    }
+}
@@ -0,0 +1,568 @@
+use crate::{Bytes, BytesMut};
+
+use core::cmp;
+use core::iter::FusedIterator;
+
+// Searching, splitting and chunking of `Bytes`. The pieces are returned as
+// `Bytes` handles into the original buffer, so they can outlive the borrow
+// used to find them.
+
+/// An iterator over the pieces of a `Bytes` separated by a delimiter.
+///
+/// This struct is created by [`Bytes::split`].
+///
+/// [`Bytes::split`]: struct.Bytes.html#method.split
+#[derive(Debug, Clone)]
+pub struct Split<'a> {
+    rest: Bytes,
+    delim: &'a [u8],
+    finished: bool,
+}
+
+/// An iterator over the lines of a `Bytes`.
+///
+/// This struct is created by [`Bytes::lines`].
+///
+/// [`Bytes::lines`]: struct.Bytes.html#method.lines
+#[derive(Debug, Clone)]
+pub struct Lines {
+    rest: Bytes,
+}
+
+/// An iterator over a `Bytes` in chunks of `size` bytes, starting at the
+/// beginning.
+///
+/// The last chunk is shorter if the length is not a multiple of `size`. This
+/// struct is created by [`Bytes::chunks`].
+///
+/// [`Bytes::chunks`]: struct.Bytes.html#method.chunks
+#[derive(Debug, Clone)]
+pub struct Chunks {
+    rest: Bytes,
+    size: usize,
+}
+
+/// An iterator over a `Bytes` in chunks of exactly `size` bytes, starting at
+/// the beginning.
+///
+/// The bytes that do not fill a whole chunk are left out and can be retrieved
+/// with [`remainder`]. This struct is created by [`Bytes::chunks_exact`].
+///
+/// [`remainder`]: #method.remainder
+/// [`Bytes::chunks_exact`]: struct.Bytes.html#method.chunks_exact
+#[derive(Debug, Clone)]
+pub struct ChunksExact {
+    rest: Bytes,
+    rem: Bytes,
+    size: usize,
+}
+
+/// An iterator over a `Bytes` in chunks of `size` bytes, starting at the end.
+///
+/// The last chunk is shorter if the length is not a multiple of `size`. This
+/// struct is created by [`Bytes::rchunks`].
+///
+/// [`Bytes::rchunks`]: struct.Bytes.html#method.rchunks
+#[derive(Debug, Clone)]
+pub struct RChunks {
+    rest: Bytes,
+    size: usize,
+}
+
+/// An iterator that splits a `BytesMut` into chunks of `size` bytes, starting
+/// at the beginning.
+///
+/// Each chunk is an independent `BytesMut` that can be mutated on its own. The
+/// last chunk is shorter if the length is not a multiple of `size`. This
+/// struct is created by [`BytesMut::split_into_chunks`].
+///
+/// [`BytesMut::split_into_chunks`]: struct.BytesMut.html#method.split_into_chunks
+#[derive(Debug)]
+pub struct IntoChunks {
+    rest: BytesMut,
+    size: usize,
+}
+
+// Returns the number of chunks of at most `size` bytes needed to hold `len`
+// bytes.
+fn num_chunks(len: usize, size: usize) -> usize {
+    len / size + (len % size != 0) as usize
+}
+
+// Returns the length of the chunk at the end of `len` bytes split into chunks
+// of `size` bytes from the front.
+fn last_chunk_len(len: usize, size: usize) -> usize {
+    match len % size {
+        0 => cmp::min(len, size),
+        rem => rem,
+    }
+}
+
+// Returns the index of the first occurrence of `needle` in `haystack`.
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    match needle.len() {
+        0 => Some(0),
+        1 => haystack.iter().position(|&b| b == needle[0]),
+        n => haystack.windows(n).position(|window| window == needle),
+    }
+}
+
+// Returns the index of the last occurrence of `needle` in `haystack`.
+fn rfind(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    match needle.len() {
+        0 => Some(haystack.len()),
+        1 => haystack.iter().rposition(|&b| b == needle[0]),
+        n => haystack.windows(n).rposition(|window| window == needle),
+    }
+}
+
+impl Bytes {
+    /// Returns the index of the first occurrence of `needle` in `self`.
+    ///
+    /// An empty `needle` matches at index 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let buf = Bytes::from_static(b"GET / HTTP/1.1\r\n");
+    ///
+    /// assert_eq!(buf.find(b" "), Some(3));
+    /// assert_eq!(buf.find(b"\r\n"), Some(14));
+    /// assert_eq!(buf.find(b"POST"), None);
+    /// ```
+    pub fn find(&self, needle: &[u8]) -> Option<usize> {
+        find(self, needle)
+    }
+
+    /// Returns the index of the last occurrence of `needle` in `self`.
+    ///
+    /// An empty `needle` matches at index `self.len()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let buf = Bytes::from_static(b"GET / HTTP/1.1\r\n");
+    ///
+    /// assert_eq!(buf.rfind(b" "), Some(5));
+    /// assert_eq!(buf.rfind(b"POST"), None);
+    /// ```
+    pub fn rfind(&self, needle: &[u8]) -> Option<usize> {
+        rfind(self, needle)
+    }
+
+    /// Splits `self` on the first occurrence of `delim`, returning the parts
+    /// before and after it. The delimiter itself is not included in either
+    /// part.
+    ///
+    /// Returns `None` if `delim` does not occur in `self`. The parts
+    /// reference the memory of `self`, see [`slice`].
+    ///
+    /// [`slice`]: #method.slice
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let buf = Bytes::from_static(b"Content-Type: text/plain");
+    /// let (name, value) = buf.split_once(b": ").unwrap();
+    ///
+    /// assert_eq!(name, "Content-Type");
+    /// assert_eq!(value, "text/plain");
+    ///
+    /// assert!(buf.split_once(b"\r\n").is_none());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `delim` is empty.
+    pub fn split_once(&self, delim: &[u8]) -> Option<(Bytes, Bytes)> {
+        assert!(!delim.is_empty(), "delimiter must not be empty");
+
+        find(self, delim).map(|pos| {
+            (self.slice(..pos), self.slice(pos + delim.len()..))
+        })
+    }
+
+    /// Returns an iterator over the parts of `self` separated by `delim`.
+    ///
+    /// Like `<[u8]>::split`, consecutive delimiters yield empty parts, and a
+    /// delimiter at the start or end of `self` yields an empty part before or
+    /// after it. Each part is a `Bytes` referencing the memory of `self`, see
+    /// [`slice`].
+    ///
+    /// [`slice`]: #method.slice
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let buf = Bytes::from_static(b"a, b,, c");
+    /// let parts: Vec<Bytes> = buf.split(b", ").collect();
+    ///
+    /// assert_eq!(parts, ["a", "b,", "c"]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `delim` is empty.
+    pub fn split<'a>(&self, delim: &'a [u8]) -> Split<'a> {
+        assert!(!delim.is_empty(), "delimiter must not be empty");
+
+        Split {
+            rest: self.clone(),
+            delim: delim,
+            finished: false,
+        }
+    }
+
+    /// Returns an iterator over the lines of `self`.
+    ///
+    /// Lines are ended by either a newline (`\n`) or a carriage return
+    /// followed by a newline (`\r\n`), which are not included in the returned
+    /// lines. As with `str::lines`, the last line does not need to be ended,
+    /// and a trailing line ending does not yield an empty line. Each line is a
+    /// `Bytes` referencing the memory of `self`, see [`slice`].
+    ///
+    /// [`slice`]: #method.slice
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let buf = Bytes::from_static(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n");
+    /// let lines: Vec<Bytes> = buf.lines().collect();
+    ///
+    /// assert_eq!(lines, ["HTTP/1.1 200 OK", "Content-Length: 0", ""]);
+    /// ```
+    pub fn lines(&self) -> Lines {
+        Lines {
+            rest: self.clone(),
+        }
+    }
+
+    /// Returns an iterator over `self` in chunks of `size` bytes, starting at
+    /// the beginning.
+    ///
+    /// The last chunk is shorter if the length of `self` is not a multiple of
+    /// `size`. The chunks reference the memory of `self` and no data is
+    /// copied, regardless of their size.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let buf = Bytes::from_static(b"hello world");
+    /// let chunks: Vec<Bytes> = buf.chunks(4).collect();
+    ///
+    /// assert_eq!(chunks, ["hell", "o wo", "rld"]);
+    /// assert_eq!(buf.chunks(4).rev().next().unwrap(), "rld");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    pub fn chunks(&self, size: usize) -> Chunks {
+        assert!(size != 0, "chunk size must be non-zero");
+
+        Chunks {
+            rest: self.clone(),
+            size: size,
+        }
+    }
+
+    /// Returns an iterator over `self` in chunks of exactly `size` bytes,
+    /// starting at the beginning.
+    ///
+    /// If the length of `self` is not a multiple of `size`, the last bytes are
+    /// not yielded and can be retrieved with `ChunksExact::remainder`. The
+    /// chunks reference the memory of `self` and no data is copied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let buf = Bytes::from_static(b"hello world");
+    /// let mut iter = buf.chunks_exact(4);
+    ///
+    /// assert_eq!(iter.len(), 2);
+    /// assert_eq!(iter.next().unwrap(), "hell");
+    /// assert_eq!(iter.next().unwrap(), "o wo");
+    /// assert!(iter.next().is_none());
+    /// assert_eq!(iter.remainder(), "rld");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    pub fn chunks_exact(&self, size: usize) -> ChunksExact {
+        assert!(size != 0, "chunk size must be non-zero");
+
+        let mut rest = self.clone();
+        let rem = rest.split_off(self.len() - self.len() % size);
+
+        ChunksExact {
+            rest: rest,
+            rem: rem,
+            size: size,
+        }
+    }
+
+    /// Returns an iterator over `self` in chunks of `size` bytes, starting at
+    /// the end.
+    ///
+    /// The last chunk, which holds the first bytes of `self`, is shorter if
+    /// the length of `self` is not a multiple of `size`. The chunks reference
+    /// the memory of `self` and no data is copied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let buf = Bytes::from_static(b"hello world");
+    /// let chunks: Vec<Bytes> = buf.rchunks(4).collect();
+    ///
+    /// assert_eq!(chunks, ["orld", "lo w", "hel"]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    pub fn rchunks(&self, size: usize) -> RChunks {
+        assert!(size != 0, "chunk size must be non-zero");
+
+        RChunks {
+            rest: self.clone(),
+            size: size,
+        }
+    }
+}
+
+impl BytesMut {
+    /// Splits `self` into chunks of `size` bytes, starting at the beginning.
+    ///
+    /// Each chunk is an independent `BytesMut`, as if it had been split off
+    /// with [`split_to`], and no data is copied. The last chunk is shorter if
+    /// the length of `self` is not a multiple of `size`.
+    ///
+    /// [`split_to`]: #method.split_to
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::BytesMut;
+    ///
+    /// let buf = BytesMut::from(&b"hello world"[..]);
+    /// let mut chunks: Vec<BytesMut> = buf.split_into_chunks(4).collect();
+    ///
+    /// chunks[1][0] = b'O';
+    ///
+    /// assert_eq!(chunks, ["hell", "O wo", "rld"]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    pub fn split_into_chunks(self, size: usize) -> IntoChunks {
+        assert!(size != 0, "chunk size must be non-zero");
+
+        IntoChunks {
+            rest: self,
+            size: size,
+        }
+    }
+}
+
+impl<'a> Iterator for Split<'a> {
+    type Item = Bytes;
+
+    fn next(&mut self) -> Option<Bytes> {
+        if self.finished {
+            return None;
+        }
+
+        match find(&self.rest, self.delim) {
+            Some(pos) => {
+                let part = self.rest.slice(..pos);
+                self.rest = self.rest.slice(pos + self.delim.len()..);
+                Some(part)
+            }
+            None => {
+                self.finished = true;
+                Some(self.rest.split_off(0))
+            }
+        }
+    }
+}
+
+impl Iterator for Lines {
+    type Item = Bytes;
+
+    fn next(&mut self) -> Option<Bytes> {
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        let line = match find(&self.rest, b"\n") {
+            Some(pos) => {
+                let line = self.rest.slice(..pos);
+                self.rest = self.rest.slice(pos + 1..);
+                line
+            }
+            None => self.rest.split_off(0),
+        };
+
+        if line.ends_with(b"\r") {
+            Some(line.slice(..line.len() - 1))
+        } else {
+            Some(line)
+        }
+    }
+}
+
+impl Iterator for Chunks {
+    type Item = Bytes;
+
+    fn next(&mut self) -> Option<Bytes> {
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        let n = cmp::min(self.size, self.rest.len());
+        Some(self.rest.split_to(n))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = num_chunks(self.rest.len(), self.size);
+        (n, Some(n))
+    }
+}
+
+impl DoubleEndedIterator for Chunks {
+    fn next_back(&mut self) -> Option<Bytes> {
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        let at = self.rest.len() - last_chunk_len(self.rest.len(), self.size);
+        Some(self.rest.split_off(at))
+    }
+}
+
+impl ExactSizeIterator for Chunks {}
+
+impl FusedIterator for Chunks {}
+
+impl ChunksExact {
+    /// Returns the bytes at the end that do not fill a whole chunk.
+    pub fn remainder(&self) -> &Bytes {
+        &self.rem
+    }
+}
+
+impl Iterator for ChunksExact {
+    type Item = Bytes;
+
+    fn next(&mut self) -> Option<Bytes> {
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        Some(self.rest.split_to(self.size))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.rest.len() / self.size;
+        (n, Some(n))
+    }
+}
+
+impl DoubleEndedIterator for ChunksExact {
+    fn next_back(&mut self) -> Option<Bytes> {
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        let at = self.rest.len() - self.size;
+        Some(self.rest.split_off(at))
+    }
+}
+
+impl ExactSizeIterator for ChunksExact {}
+
+impl FusedIterator for ChunksExact {}
+
+impl Iterator for RChunks {
+    type Item = Bytes;
+
+    fn next(&mut self) -> Option<Bytes> {
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        let at = self.rest.len() - cmp::min(self.size, self.rest.len());
+        Some(self.rest.split_off(at))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = num_chunks(self.rest.len(), self.size);
+        (n, Some(n))
+    }
+}
+
+impl DoubleEndedIterator for RChunks {
+    fn next_back(&mut self) -> Option<Bytes> {
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        let n = last_chunk_len(self.rest.len(), self.size);
+        Some(self.rest.split_to(n))
+    }
+}
+
+impl ExactSizeIterator for RChunks {}
+
+impl FusedIterator for RChunks {}
+
+impl Iterator for IntoChunks {
+    type Item = BytesMut;
+
+    fn next(&mut self) -> Option<BytesMut> {
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        let n = cmp::min(self.size, self.rest.len());
+        Some(self.rest.split_to(n))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = num_chunks(self.rest.len(), self.size);
+        (n, Some(n))
+    }
+}
+
+impl DoubleEndedIterator for IntoChunks {
+    fn next_back(&mut self) -> Option<BytesMut> {
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        let at = self.rest.len() - last_chunk_len(self.rest.len(), self.size);
+        Some(self.rest.split_off(at))
+    }
+}
+
+impl ExactSizeIterator for IntoChunks {}
+
+impl FusedIterator for IntoChunks {}
@@ -1,6 +1,8 @@
 #![deny(warnings, rust_2018_idioms)]
 
-use bytes::Buf;
+use bytes::{Buf, Bytes, IntoBuf, TryGetError};
+use bytes::buf::{LenPrefix, PrefixedError, VarintError};
+#[cfg(feature = "std")]
 use std::io::IoSlice;
 
 #[test]
@@ -43,6 +45,7 @@ fn test_get_u16_buffer_underflow() {
 }
 
 #[test]
+#[cfg(feature = "std")]
 fn test_bufs_vec() {
     let buf = &b"hello world"[..];
 
@@ -53,3 +56,407 @@ fn test_bufs_vec() {
 
     assert_eq!(1, buf.bytes_vectored(&mut dst[..]));
 }
+
+#[test]
+fn test_try_get_ok() {
+    let mut buf = &b"\x21\x54\x01\x02\x03\x04zomg"[..];
+    assert_eq!(Ok(0x21), buf.try_get_u8());
+    assert_eq!(Ok(0x54), buf.try_get_i8());
+    assert_eq!(Ok(0x0102), buf.try_get_u16());
+    assert_eq!(Ok(0x0403), buf.try_get_u16_le());
+    assert_eq!(Ok(0x7a6f6d67), buf.try_get_u32());
+    assert!(!buf.has_remaining());
+
+    let mut buf = &b"\x3F\x99\x99\x9A\xff\xfe"[..];
+    assert_eq!(Ok(1.2f32), buf.try_get_f32());
+    assert_eq!(Ok(0xfffe), buf.try_get_uint(2));
+}
+
+#[test]
+fn test_try_get_underflow() {
+    let mut buf = &b"\x21\x54\x01"[..];
+
+    assert_eq!(Err(TryGetError { requested: 4, available: 3 }), buf.try_get_u32());
+    assert_eq!(Err(TryGetError { requested: 8, available: 3 }), buf.try_get_f64_le());
+    assert_eq!(Err(TryGetError { requested: 16, available: 3 }), buf.try_get_i128());
+    assert_eq!(Err(TryGetError { requested: 5, available: 3 }), buf.try_get_uint_le(5));
+    assert_eq!(buf, b"\x21\x54\x01");
+
+    let mut dst = [0; 4];
+    assert_eq!(Err(TryGetError { requested: 4, available: 3 }), buf.try_copy_to_slice(&mut dst));
+    assert_eq!(dst, [0; 4]);
+
+    assert_eq!(Ok(()), buf.try_copy_to_slice(&mut dst[..3]));
+    assert_eq!(Err(TryGetError { requested: 1, available: 0 }), buf.try_get_u8());
+}
+
+#[test]
+fn test_try_get_across_chunks() {
+    let mut buf = Bytes::from_static(b"\x01\x02").chain(Bytes::from_static(b"\x03"));
+
+    assert_eq!(Err(TryGetError { requested: 4, available: 3 }), buf.try_get_u32());
+    assert_eq!(Ok(0x010203), buf.try_get_uint(3));
+}
+
+#[test]
+fn test_try_get_error_display() {
+    let err = TryGetError { requested: 4, available: 1 };
+    assert_eq!(
+        err.to_string(),
+        "not enough bytes remaining in buffer to read value (requested 4 but only 1 available)"
+    );
+}
+
+#[test]
+fn test_peek() {
+    let buf = &b"\x01\x02\x03\x04\x05\x06\x07\x08zomg"[..];
+
+    assert_eq!(0x01, buf.peek_u8());
+    assert_eq!(0x0102, buf.peek_u16());
+    assert_eq!(0x04030201, buf.peek_u32_le());
+    assert_eq!(0x0102030405060708, buf.peek_u64());
+
+    let mut dst = [0; 12];
+    buf.peek_slice(&mut dst);
+    assert_eq!(&dst[8..], b"zomg");
+    assert_eq!(buf.remaining(), 12);
+}
+
+#[test]
+#[should_panic]
+fn test_peek_u32_buffer_underflow() {
+    let buf = &b"\x21\x54"[..];
+    buf.peek_u32();
+}
+
+#[test]
+fn test_peek_vec_deque() {
+    use std::collections::VecDeque;
+
+    let mut buf = VecDeque::new();
+    buf.extend(b"\x03\x04\x05");
+
+    // Wraps around the start of the ring buffer
+    buf.push_front(0x02);
+    buf.push_front(0x01);
+    assert!(buf.bytes().len() < 5);
+
+    assert_eq!(0x01020304, buf.peek_u32());
+
+    let mut dst = [0; 5];
+    buf.peek_slice(&mut dst);
+    assert_eq!(&dst, b"\x01\x02\x03\x04\x05");
+    assert_eq!(buf.remaining(), 5);
+}
+
+// A `Buf` made of several chunks, which it exposes through `bytes_at`.
+struct Chunked {
+    chunks: Vec<&'static [u8]>,
+}
+
+impl Buf for Chunked {
+    fn remaining(&self) -> usize {
+        self.chunks.iter().map(|chunk| chunk.len()).sum()
+    }
+
+    fn bytes(&self) -> &[u8] {
+        self.bytes_at(0)
+    }
+
+    fn bytes_at(&self, mut offset: usize) -> &[u8] {
+        for chunk in &self.chunks {
+            if offset < chunk.len() {
+                return &chunk[offset..];
+            }
+
+            offset -= chunk.len();
+        }
+
+        &[]
+    }
+
+    fn advance(&mut self, mut cnt: usize) {
+        while cnt > 0 {
+            let n = std::cmp::min(cnt, self.chunks[0].len());
+            self.chunks[0] = &self.chunks[0][n..];
+            cnt -= n;
+
+            if self.chunks[0].is_empty() {
+                self.chunks.remove(0);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_peek_across_chunks_default() {
+    let buf = Chunked { chunks: vec![b"\x01", b"\x02\x03", b"\x04\x05"] };
+
+    assert_eq!(0x01020304, buf.peek_u32());
+
+    let mut dst = [0; 5];
+    buf.peek_slice(&mut dst);
+    assert_eq!(&dst, b"\x01\x02\x03\x04\x05");
+    assert_eq!(buf.remaining(), 5);
+}
+
+// Forwards everything but `bytes_at` to a `Chunked`.
+#[cfg(feature = "std")]
+struct Vectored(Chunked);
+
+#[cfg(feature = "std")]
+impl Buf for Vectored {
+    fn remaining(&self) -> usize {
+        self.0.remaining()
+    }
+
+    fn bytes(&self) -> &[u8] {
+        self.0.bytes()
+    }
+
+    fn bytes_vectored<'a>(&'a self, dst: &mut [IoSlice<'a>]) -> usize {
+        let mut n = 0;
+
+        for (slot, chunk) in dst.iter_mut().zip(&self.0.chunks) {
+            *slot = IoSlice::new(chunk);
+            n += 1;
+        }
+
+        n
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        self.0.advance(cnt)
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_peek_across_chunks_vectored() {
+    let mut buf = Vectored(Chunked { chunks: vec![b"\x01", b"\x02\x03", b"ab\0c"] });
+
+    assert_eq!(buf.bytes_at(1), b"");
+    assert_eq!(0x01020361, buf.peek_u32());
+    assert_eq!(buf.get_cstr().unwrap(), &b"\x01\x02\x03ab"[..]);
+    assert_eq!(buf.remaining(), 1);
+}
+
+#[test]
+fn test_bytes_at() {
+    let buf = (&b"hel"[..]).chain(&b"lo world"[..]);
+    assert_eq!(buf.bytes_at(1), b"el");
+    assert_eq!(buf.bytes_at(3), b"lo world");
+    assert_eq!(buf.bytes_at(11), b"");
+    assert_eq!(buf.bytes_at(20), b"");
+
+    let buf = buf.take(5);
+    assert_eq!(buf.bytes_at(1), b"el");
+    assert_eq!(buf.bytes_at(3), b"lo");
+    assert_eq!(buf.bytes_at(5), b"");
+
+    let mut buf = std::collections::VecDeque::new();
+    buf.extend(b"\x03\x04\x05");
+    buf.push_front(0x02);
+    buf.push_front(0x01);
+    let first = buf.bytes().len();
+    assert_eq!(buf.bytes_at(first)[0], 0x01 + first as u8);
+    assert_eq!(buf.bytes_at(5), b"");
+}
+
+#[test]
+fn test_get_varint() {
+    let mut buf = &b"\x00\x7f\xac\x02\x01\x04"[..];
+
+    assert_eq!(Ok(0), buf.get_uvarint());
+    assert_eq!(Ok(127), buf.get_uvarint32());
+    assert_eq!(Ok(300), buf.get_uvarint());
+    assert_eq!(Ok(-1), buf.get_ivarint());
+    assert_eq!(Ok(2), buf.get_ivarint32());
+    assert!(!buf.has_remaining());
+}
+
+#[test]
+fn test_get_varint_errors() {
+    let mut buf = &b"\xac"[..];
+    assert_eq!(Err(VarintError::Truncated), buf.get_uvarint());
+    assert_eq!(buf, b"\xac");
+
+    let mut buf = &b"\x80\x80\x80\x80\x10"[..];
+    assert_eq!(Err(VarintError::Overflow), buf.get_uvarint32());
+    assert_eq!(Err(VarintError::Overflow), buf.get_ivarint32());
+    assert_eq!(Ok(1 << 32), buf.get_uvarint());
+
+    let mut buf = &[0xff; 11][..];
+    assert_eq!(Err(VarintError::Overflow), buf.get_uvarint());
+    assert_eq!(buf.remaining(), 11);
+}
+
+#[test]
+fn test_varint_error_from_try_get() {
+    // A tag byte followed by a varint
+    fn read(buf: &mut &[u8]) -> Result<(u8, u64), VarintError> {
+        Ok((buf.try_get_u8()?, buf.get_uvarint()?))
+    }
+
+    assert_eq!(read(&mut &b"\x01\x96\x01"[..]), Ok((1, 150)));
+    assert_eq!(read(&mut &b""[..]), Err(VarintError::Truncated));
+}
+
+#[test]
+fn test_get_prefixed_bytes() {
+    let mut buf = &b"\x00\x03abc\x02\x00\x00\x00de\x01f"[..];
+
+    assert_eq!(buf.get_prefixed_bytes(LenPrefix::U16Be).unwrap(), &b"abc"[..]);
+    assert_eq!(buf.get_prefixed_bytes(LenPrefix::U32Le).unwrap(), &b"de"[..]);
+    assert_eq!(buf.get_prefixed_bytes(LenPrefix::Varint).unwrap(), &b"f"[..]);
+    assert!(!buf.has_remaining());
+}
+
+#[test]
+fn test_get_prefixed_bytes_errors() {
+    let mut buf = &b"\x00"[..];
+    assert_eq!(buf.get_prefixed_bytes(LenPrefix::U16Le), Err(PrefixedError::Truncated));
+
+    let mut buf = &b"\x00\x00\x00\x00\x00\x00\x00\x04abc"[..];
+    assert_eq!(buf.get_prefixed_bytes(LenPrefix::U64Be), Err(PrefixedError::Truncated));
+    assert_eq!(
+        buf.get_prefixed_bytes_limited(LenPrefix::U64Be, 3),
+        Err(PrefixedError::TooLong { len: 4, limit: 3 }));
+    assert_eq!(buf.remaining(), 11);
+
+    let mut buf = &[0xff; 11][..];
+    assert_eq!(buf.get_prefixed_bytes(LenPrefix::Varint), Err(PrefixedError::InvalidPrefix));
+}
+
+#[test]
+fn test_get_prefixed_bytes_zero_copy() {
+    let data = Bytes::from(&b"\x28........................................"[..]);
+    let mut buf = data.clone();
+
+    let field = buf.get_prefixed_bytes(LenPrefix::U8).unwrap();
+    assert_eq!(field.len(), 40);
+    assert_eq!(field.as_ptr(), data[1..].as_ptr());
+}
+
+#[test]
+fn test_prefixed_error_from() {
+    // A version byte, a varint count and a length-prefixed payload
+    fn read(buf: &mut &[u8]) -> Result<(u8, u64, Bytes), PrefixedError> {
+        let version = buf.try_get_u8()?;
+        let count = buf.get_uvarint()?;
+        let payload = buf.get_prefixed_bytes(LenPrefix::U8)?;
+        Ok((version, count, payload))
+    }
+
+    assert_eq!(read(&mut &b"\x01\x02\x03abc"[..]), Ok((1, 2, Bytes::from(&b"abc"[..]))));
+    assert_eq!(read(&mut &b""[..]), Err(PrefixedError::Truncated));
+    assert_eq!(read(&mut &b"\x01\x80"[..]), Err(PrefixedError::Truncated));
+    assert_eq!(read(&mut &b"\x01\x02\x03ab"[..]), Err(PrefixedError::Truncated));
+    assert_eq!(PrefixedError::from(VarintError::Overflow), PrefixedError::InvalidPrefix);
+}
+
+#[test]
+fn test_get_cstr() {
+    let mut buf = &b"\0abc\0"[..];
+    assert_eq!(buf.get_cstr().unwrap(), &b""[..]);
+    assert_eq!(buf.get_cstr().unwrap(), &b"abc"[..]);
+    assert_eq!(buf.get_cstr(), Err(PrefixedError::Unterminated));
+
+    // Across chunks
+    let mut buf = (&b"hel"[..]).chain(&b"lo\0world"[..]);
+    assert_eq!(buf.get_cstr().unwrap(), &b"hello"[..]);
+    assert_eq!(buf.get_cstr(), Err(PrefixedError::Unterminated));
+    assert_eq!(buf.remaining(), 5);
+}
+
+#[test]
+fn test_get_cstr_many_chunks() {
+    let mut buf = Chunked { chunks: vec![b"he", b"l", b"lo\0", b"wor", b"ld"] };
+    assert_eq!(buf.get_cstr().unwrap(), &b"hello"[..]);
+    assert_eq!(buf.get_cstr(), Err(PrefixedError::Unterminated));
+    assert_eq!(buf.remaining(), 5);
+}
+
+#[test]
+fn test_get_cstr_limited() {
+    let mut buf = &b"abc\0"[..];
+    assert_eq!(buf.get_cstr_limited(3).unwrap(), &b"abc"[..]);
+
+    // The terminator is past the limit
+    let mut buf = (&b"ab"[..]).chain(&b"cd\0"[..]);
+    assert_eq!(buf.get_cstr_limited(3), Err(PrefixedError::TooLong { len: 4, limit: 3 }));
+    assert_eq!(buf.remaining(), 5);
+
+    // Not enough data to tell yet
+    let mut buf = &b"ab"[..];
+    assert_eq!(buf.get_cstr_limited(3), Err(PrefixedError::Unterminated));
+
+    let mut buf = &b"\0"[..];
+    assert_eq!(buf.get_cstr_limited(0).unwrap(), &b""[..]);
+    assert_eq!(buf.get_cstr_limited(usize::max_value()), Err(PrefixedError::Unterminated));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_cursor() {
+    use std::io::Cursor;
+
+    let mut buf = Cursor::new(b"\x01\x02hello".to_vec());
+    buf.set_position(1);
+
+    assert_eq!(buf.remaining(), 6);
+    assert_eq!(buf.bytes(), b"\x02hello");
+    assert_eq!(buf.get_u8(), 2);
+    assert_eq!(buf.position(), 2);
+
+    let mut dst = [0; 5];
+    buf.copy_to_slice(&mut dst);
+    assert_eq!(&dst, b"hello");
+    assert!(!buf.has_remaining());
+
+    // A position past the end has nothing remaining
+    let mut buf = Cursor::new(&b"abc"[..]);
+    buf.set_position(10);
+    assert_eq!(buf.remaining(), 0);
+    assert_eq!(buf.bytes(), b"");
+}
+
+#[test]
+#[should_panic]
+#[cfg(feature = "std")]
+fn test_cursor_advance_past_end() {
+    let mut buf = std::io::Cursor::new(&b"abc"[..]);
+    buf.advance(4);
+}
+
+#[test]
+fn test_get_array() {
+    let mut buf = Bytes::from_static(b"\x01\x02\x03").chain(&b"\x04\x05"[..]);
+
+    // Spans both halves of the chain
+    let arr: [u8; 4] = buf.get_array();
+    assert_eq!(arr, [1, 2, 3, 4]);
+
+    assert_eq!(buf.try_get_array::<2>(), Err(TryGetError { requested: 2, available: 1 }));
+    assert_eq!(buf.try_get_array(), Ok([5]));
+    assert_eq!(buf.try_get_array(), Ok([]));
+}
+
+#[test]
+fn test_array_into_buf() {
+    let mut buf = [0xde, 0xad, 0xbe, 0xef, 0x01].into_buf();
+    assert_eq!(buf.get_u32(), 0xdeadbeef);
+    assert_eq!(buf.remaining(), 1);
+    assert_eq!(buf.into_inner(), [0xde, 0xad, 0xbe, 0xef, 0x01]);
+
+    let mut buf = 7u8.into_buf();
+    assert_eq!(buf.get_u8(), 7);
+    assert!(!buf.has_remaining());
+
+    let mut buf = (-1i8).into_buf();
+    assert_eq!(buf.get_i8(), -1);
+
+    let arr = [1, 2];
+    let buf: &[u8] = (&arr).into_buf();
+    assert_eq!(buf, &[1, 2]);
+}
@@ -0,0 +1,161 @@
+#![deny(warnings, rust_2018_idioms)]
+
+use bytes::{Buf, Bytes};
+use bytes::buf::BufList;
+#[cfg(feature = "std")]
+use std::io::IoSlice;
+
+fn list(segments: &[&'static [u8]]) -> BufList {
+    segments.iter().map(|s| Bytes::from_static(s)).collect()
+}
+
+#[test]
+fn empty() {
+    let mut list = BufList::new();
+    assert_eq!(list.remaining(), 0);
+    assert_eq!(list.bytes(), b"");
+    assert_eq!(list.num_segments(), 0);
+
+    list.push_back(Bytes::new());
+    list.push_front(Bytes::new());
+    assert_eq!(list.num_segments(), 0);
+}
+
+#[test]
+fn read_across_segments() {
+    let mut list = list(&[b"\x01\x02", b"\x03", b"\x04\x05\x06"]);
+    assert_eq!(list.remaining(), 6);
+
+    assert_eq!(list.get_u16(), 0x0102);
+    assert_eq!(list.get_u32(), 0x03040506);
+    assert!(!list.has_remaining());
+    assert_eq!(list.num_segments(), 0);
+}
+
+#[test]
+fn advance() {
+    let mut list = list(&[b"hello", b" ", b"world"]);
+
+    list.advance(2);
+    assert_eq!(list.bytes(), b"llo");
+
+    list.advance(4);
+    assert_eq!(list.bytes(), b"world");
+    assert_eq!(list.num_segments(), 1);
+
+    list.advance(5);
+    assert_eq!(list.remaining(), 0);
+}
+
+#[test]
+#[should_panic]
+fn advance_past_remaining() {
+    let mut list = list(&[b"hello"]);
+    list.advance(6);
+}
+
+#[test]
+fn push_front_and_back() {
+    let mut list = BufList::new();
+    list.push_back(Bytes::from_static(b"b"));
+    list.push_front(Bytes::from_static(b"a"));
+    list.extend(vec![Bytes::from_static(b"c"), Bytes::from_static(b"d")]);
+
+    assert_eq!(list.num_segments(), 4);
+
+    let full: Vec<u8> = list.collect();
+    assert_eq!(full, b"abcd");
+}
+
+#[test]
+fn split_to() {
+    let data = Bytes::from(vec![7; 100]);
+    let mut list = BufList::from(data.slice(0..60));
+    list.push_back(Bytes::from_static(b"tail"));
+
+    let head = list.split_to(30);
+    assert_eq!(head.remaining(), 30);
+    assert_eq!(list.remaining(), 34);
+
+    // The split segment still references the original memory
+    assert_eq!(head.bytes().as_ptr(), data.as_ptr());
+    assert_eq!(list.bytes().as_ptr(), data[30..].as_ptr());
+
+    let mid = list.split_to(30);
+    assert_eq!(mid.num_segments(), 1);
+    assert_eq!(list.bytes(), b"tail");
+
+    let all = list.split_to(4);
+    assert_eq!(all.num_segments(), 1);
+    assert_eq!(list.num_segments(), 0);
+    assert_eq!(list.split_to(0).remaining(), 0);
+}
+
+#[test]
+#[should_panic]
+fn split_to_out_of_bounds() {
+    let mut list = list(&[b"hello", b"world"]);
+    list.split_to(11);
+}
+
+#[test]
+fn copy_to_bytes_single_segment() {
+    let data = Bytes::from(vec![1; 100]);
+    let mut list = BufList::from(data.clone());
+    list.push_back(Bytes::from_static(b"tail"));
+
+    let a = list.copy_to_bytes(60);
+    assert_eq!(a.as_ptr(), data.as_ptr());
+
+    let b = list.copy_to_bytes(40);
+    assert_eq!(b.as_ptr(), data[60..].as_ptr());
+    assert_eq!(list.num_segments(), 1);
+    assert_eq!(list.remaining(), 4);
+}
+
+#[test]
+fn copy_to_bytes_multiple_segments() {
+    let mut list = list(&[b"hello", b" ", b"world"]);
+
+    assert_eq!(list.copy_to_bytes(8), b"hello wo"[..]);
+    assert_eq!(list.bytes(), b"rld");
+    assert_eq!(list.copy_to_bytes(0), b""[..]);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn bytes_vectored() {
+    let list = list(&[b"hello", b" ", b"world"]);
+
+    let b1: &[u8] = &mut [];
+    let b2: &[u8] = &mut [];
+    let mut iovecs = [IoSlice::new(b1), IoSlice::new(b2)];
+
+    assert_eq!(list.bytes_vectored(&mut iovecs), 2);
+    assert_eq!(iovecs[0][..], b"hello"[..]);
+    assert_eq!(iovecs[1][..], b" "[..]);
+
+    let b3: &[u8] = &mut [];
+    let mut iovecs = [IoSlice::new(b1), IoSlice::new(b2), IoSlice::new(b3), IoSlice::new(b3)];
+
+    assert_eq!(list.bytes_vectored(&mut iovecs), 3);
+    assert_eq!(iovecs[2][..], b"world"[..]);
+}
+
+#[test]
+fn peek_across_segments() {
+    let list = list(&[b"\x01", b"\x02\x03", b"\x04\x05"]);
+
+    assert_eq!(0x01020304, list.peek_u32());
+    assert_eq!(list.remaining(), 5);
+}
+
+#[test]
+fn bytes_at() {
+    let list = list(&[b"\x01", b"\x02\x03", b"\x04\x05"]);
+
+    assert_eq!(list.bytes_at(0), b"\x01");
+    assert_eq!(list.bytes_at(2), b"\x03");
+    assert_eq!(list.bytes_at(3), b"\x04\x05");
+    assert_eq!(list.bytes_at(5), b"");
+}
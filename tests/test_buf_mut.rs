@@ -1,9 +1,9 @@
 #![deny(warnings, rust_2018_idioms)]
 
 use bytes::{BufMut, BytesMut};
+use bytes::buf::{Chain, UninitSlice};
 use std::usize;
 use std::fmt::Write;
-use std::io::IoSliceMut;
 
 #[test]
 fn test_vec_as_mut_buf() {
@@ -11,9 +11,7 @@ fn test_vec_as_mut_buf() {
 
     assert_eq!(buf.remaining_mut(), usize::MAX);
 
-    unsafe {
-        assert!(buf.bytes_mut().len() >= 64);
-    }
+    assert!(buf.chunk_mut().len() >= 64);
 
     buf.put(&b"zomg"[..]);
 
@@ -71,13 +69,54 @@ fn test_clone() {
 #[test]
 fn test_bufs_vec_mut() {
     let mut buf = BytesMut::from(&b"hello world"[..]);
-    let b1: &mut [u8] = &mut [];
-    let b2: &mut [u8] = &mut [];
-    let mut dst = [IoSliceMut::new(b1), IoSliceMut::new(b2)];
+    let mut dst: [&mut UninitSlice; 2] = Default::default();
 
-    unsafe {
-        assert_eq!(1, buf.bytes_vectored_mut(&mut dst[..]));
+    assert_eq!(1, buf.chunks_vectored_mut(&mut dst[..]));
+}
+
+#[test]
+fn test_chain_chunks_vectored_mut() {
+    let mut a = [0; 4];
+    let mut b = [0; 4];
+
+    {
+        let mut buf = Chain::new(&mut a[..], &mut b[..]);
+        let mut dst: [&mut UninitSlice; 3] = Default::default();
+
+        assert_eq!(2, buf.chunks_vectored_mut(&mut dst[..]));
+        assert_eq!(4, dst[0].len());
+        assert_eq!(4, dst[1].len());
+        assert!(dst[2].is_empty());
+
+        dst[0].copy_from_slice(b"abcd");
+        dst[1][..2].copy_from_slice(b"ef");
+        unsafe { buf.advance_mut(6); }
+
+        let mut dst: [&mut UninitSlice; 3] = Default::default();
+        assert_eq!(1, buf.chunks_vectored_mut(&mut dst[..]));
+        assert_eq!(2, dst[0].len());
     }
+
+    assert_eq!(b"abcd", &a);
+    assert_eq!(b"ef\0\0", &b);
+}
+
+#[test]
+fn test_uninit_slice() {
+    let mut data = [b'f', b'o', b'o', b'!'];
+    let slice = UninitSlice::new(&mut data[..]);
+
+    slice.write_byte(3, b'?');
+    slice[..=2].copy_from_slice(b"bar");
+
+    assert_eq!(b"bar?", &data);
+}
+
+#[test]
+#[should_panic]
+fn test_uninit_slice_write_byte_out_of_bounds() {
+    let mut data = [0; 3];
+    UninitSlice::new(&mut data[..]).write_byte(3, 0);
 }
 
 #[test]
@@ -86,3 +125,142 @@ fn test_mut_slice() {
     let mut s = &mut v[..];
     s.put_u32(42);
 }
+
+#[test]
+fn test_put_varint() {
+    use bytes::Buf;
+
+    let mut buf = BytesMut::new();
+    buf.put_uvarint(0);
+    buf.put_uvarint(u64::max_value());
+    buf.put_uvarint32(300);
+    buf.put_ivarint(i64::min_value());
+    buf.put_ivarint32(-64);
+
+    assert_eq!(buf.len(), 1 + 10 + 2 + 10 + 1);
+
+    let mut buf = buf.freeze();
+    assert_eq!(Ok(0), buf.get_uvarint());
+    assert_eq!(Ok(u64::max_value()), buf.get_uvarint());
+    assert_eq!(Ok(300), buf.get_uvarint32());
+    assert_eq!(Ok(i64::min_value()), buf.get_ivarint());
+    assert_eq!(Ok(-64), buf.get_ivarint32());
+}
+
+#[test]
+fn test_put_prefixed() {
+    use bytes::buf::LenPrefix;
+
+    let mut buf = vec![];
+    buf.put_prefixed(LenPrefix::U16Le, b"ab");
+    buf.put_prefixed(LenPrefix::U32Be, b"");
+    buf.put_prefixed(LenPrefix::Varint, &[7; 200]);
+
+    assert_eq!(&buf[..8], b"\x02\x00ab\x00\x00\x00\x00");
+    assert_eq!(&buf[8..10], b"\xc8\x01");
+    assert_eq!(buf.len(), 210);
+}
+
+#[test]
+#[should_panic]
+fn test_put_prefixed_too_long() {
+    use bytes::buf::LenPrefix;
+
+    let mut buf = vec![];
+    buf.put_prefixed(LenPrefix::U8, &[0; 256]);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_cursor_slice() {
+    use std::io::Cursor;
+
+    let mut data = [0; 8];
+
+    {
+        let mut buf = Cursor::new(&mut data[..]);
+        buf.set_position(2);
+        assert_eq!(buf.remaining_mut(), 6);
+
+        buf.put_u16(0x0102);
+        buf.put_slice(b"abcd");
+        assert_eq!(buf.position(), 8);
+        assert_eq!(buf.remaining_mut(), 0);
+    }
+
+    assert_eq!(&data, b"\x00\x00\x01\x02abcd");
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_cursor_vec() {
+    use std::io::Cursor;
+
+    let mut buf = Cursor::new(b"hello".to_vec());
+    buf.set_position(3);
+
+    // Overwrites, then extends
+    buf.put_slice(b"p me");
+    assert_eq!(buf.get_ref(), b"help me");
+    assert_eq!(buf.position(), 7);
+
+    // Pads with zeros when past the end
+    buf.set_position(9);
+    buf.put_u8(b'!');
+    assert_eq!(buf.get_ref(), b"help me\x00\x00!");
+    assert_eq!(buf.position(), 10);
+}
+
+#[test]
+fn test_put_array() {
+    let mut buf = BytesMut::new();
+    buf.put_array([1, 2, 3]);
+    buf.put_array([]);
+    buf.put_array(*b"abc");
+    assert_eq!(buf, b"\x01\x02\x03abc"[..]);
+}
+
+#[test]
+#[should_panic]
+fn test_put_array_overflow() {
+    let mut dst = [0; 2];
+    (&mut dst[..]).put_array([0; 3]);
+}
+
+#[test]
+fn test_put_hex() {
+    let mut buf = BytesMut::new();
+    buf.put_hex(b"\x01\x23\xab");
+    buf.put_hex(b"");
+    assert_eq!(buf, b"0123ab"[..]);
+
+    let mut buf = vec![];
+    buf.put_hex(&[0xff; 100]);
+    assert_eq!(buf, "ff".repeat(100).as_bytes());
+}
+
+#[test]
+fn test_put_base64() {
+    use bytes::encoding::Base64;
+
+    let mut buf = BytesMut::new();
+    buf.put_base64(b"\xfb\xff", Base64::Standard);
+    buf.put_u8(b' ');
+    buf.put_base64(b"\xfb\xff", Base64::UrlSafeNoPad);
+    assert_eq!(buf, b"+/8= -_8"[..]);
+
+    let data: Vec<u8> = (0..200).map(|i| i as u8).collect();
+    let mut buf = vec![];
+    buf.put_base64(&data, Base64::Standard);
+    assert_eq!(buf.len(), 268);
+    assert_eq!(&buf[buf.len() - 4..], b"xsc=");
+}
+
+#[test]
+#[should_panic]
+fn test_put_base64_overflow() {
+    use bytes::encoding::Base64;
+
+    let mut dst = [0; 7];
+    (&mut dst[..]).put_base64(b"hello", Base64::Standard);
+}
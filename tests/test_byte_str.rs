@@ -0,0 +1,202 @@
+#![deny(warnings, rust_2018_idioms)]
+
+use bytes::{Buf, ByteStr, Bytes, BytesMut};
+
+const LONG: &'static str = "mary had a little lamb, little lamb, little lamb";
+
+#[test]
+fn from_utf8() {
+    let s = ByteStr::from_utf8(Bytes::from(LONG)).unwrap();
+    assert_eq!(s, LONG);
+    assert_eq!(s.as_bytes(), LONG.as_bytes());
+
+    let err = ByteStr::from_utf8(Bytes::from_static(b"hello \xf0\x9f")).unwrap_err();
+    assert_eq!(err.utf8_error().valid_up_to(), 6);
+    assert_eq!(err.as_bytes(), b"hello \xf0\x9f");
+    assert_eq!(err.into_bytes(), &b"hello \xf0\x9f"[..]);
+}
+
+#[test]
+fn fmt() {
+    let s = ByteStr::from_static("a \"b\"");
+    assert_eq!(format!("{:?}", s), "\"a \\\"b\\\"\"");
+    assert_eq!(format!("{}", s), "a \"b\"");
+}
+
+#[test]
+fn slice() {
+    let s = ByteStr::from(LONG);
+    let a = s.slice(5..);
+
+    assert_eq!(a, &LONG[5..]);
+    assert_eq!(a.as_ptr(), s[5..].as_ptr());
+
+    let b = s.slice_ref(s.trim_end_matches("lamb"));
+    assert_eq!(b, &LONG[..LONG.len() - 4]);
+    assert_eq!(b.as_ptr(), s.as_ptr());
+}
+
+#[test]
+#[should_panic]
+fn slice_not_char_boundary() {
+    let s = ByteStr::from("grüße");
+    s.slice(..3);
+}
+
+#[test]
+fn split() {
+    let mut s = ByteStr::from("grüße, welt");
+
+    let head = s.split_to(7);
+    assert_eq!(head, "grüße");
+    assert_eq!(s, ", welt");
+
+    let tail = s.split_off(2);
+    assert_eq!(s, ", ");
+    assert_eq!(tail, "welt");
+}
+
+#[test]
+#[should_panic]
+fn split_to_not_char_boundary() {
+    let mut s = ByteStr::from("grüße");
+    s.split_to(3);
+}
+
+#[test]
+#[should_panic]
+fn split_off_out_of_bounds() {
+    let mut s = ByteStr::from("hello");
+    s.split_off(6);
+}
+
+#[test]
+fn truncate() {
+    let mut s = ByteStr::from("grüße");
+    s.truncate(10);
+    assert_eq!(s, "grüße");
+    s.truncate(4);
+    assert_eq!(s, "grü");
+}
+
+#[test]
+fn into_string_reuses_allocation() {
+    let src = String::from(LONG);
+    let ptr = src.as_ptr();
+
+    let s = ByteStr::from(src);
+    assert_eq!(s.as_ptr(), ptr);
+
+    let dst = String::from(s);
+    assert_eq!(dst, LONG);
+    assert_eq!(dst.as_ptr(), ptr);
+}
+
+#[test]
+fn into_string_truncated() {
+    let src = String::from(LONG);
+    let ptr = src.as_ptr();
+
+    let mut s = ByteStr::from(src);
+    s.truncate(LONG.len() - 4);
+
+    let dst = String::from(s);
+    assert_eq!(dst, &LONG[..LONG.len() - 4]);
+    assert_eq!(dst.as_ptr(), ptr);
+}
+
+#[test]
+fn into_string_vec_offset() {
+    let src = LONG.as_bytes().to_vec();
+    let ptr = src.as_ptr();
+
+    let mut buf = BytesMut::from(src);
+    buf.advance(5);
+
+    let s = ByteStr::from_utf8(buf.freeze()).unwrap();
+
+    let dst = String::from(s);
+    assert_eq!(dst, &LONG[5..]);
+    assert_eq!(dst.as_ptr(), ptr);
+}
+
+#[test]
+fn into_string_unique_arc() {
+    let src = String::from(LONG);
+    let ptr = src.as_ptr();
+
+    let mut s = ByteStr::from(src);
+
+    // Promote the storage to a shared buffer, then make it unique again
+    let head = s.split_to(5);
+    drop(head);
+
+    let dst = String::from(s);
+    assert_eq!(dst, &LONG[5..]);
+    assert_eq!(dst.as_ptr(), ptr);
+}
+
+#[test]
+fn into_string_unique_arc_written_after_split() {
+    let mut buf = BytesMut::with_capacity(128);
+    buf.extend_from_slice(b"0123456789");
+    let ptr = buf.as_ptr();
+
+    // Promote the storage to a shared buffer while it holds 10 bytes, then
+    // keep writing after the storage is unique again.
+    let head = buf.split_to(5);
+    drop(head);
+    buf.extend_from_slice(LONG.as_bytes());
+
+    let s = ByteStr::from_utf8(buf.freeze()).unwrap();
+    assert!(s.len() > 31);
+
+    let dst = String::from(s);
+    assert_eq!(dst, format!("56789{}", LONG));
+    assert_eq!(dst.as_ptr(), ptr);
+}
+
+#[test]
+fn into_string_unique_arc_uninit_prefix() {
+    let mut head = BytesMut::with_capacity(128);
+    let ptr = head.as_ptr();
+
+    // Nothing is ever written to the first 64 bytes of the allocation
+    let mut buf = head.split_off(64);
+    drop(head);
+    buf.extend_from_slice(LONG.as_bytes());
+
+    let s = ByteStr::from_utf8(buf.freeze()).unwrap();
+
+    let dst = String::from(s);
+    assert_eq!(dst, LONG);
+    assert_eq!(dst.as_ptr(), ptr);
+}
+
+#[test]
+fn into_string_shared_copies() {
+    let s = ByteStr::from(String::from(LONG));
+    let other = s.slice(..);
+
+    let dst = String::from(s);
+    assert_eq!(dst, LONG);
+    assert_ne!(dst.as_ptr(), other.as_ptr());
+    assert_eq!(other, LONG);
+}
+
+#[test]
+fn into_string_static() {
+    let s = ByteStr::from_static("hello");
+    assert_eq!(String::from(s), "hello");
+}
+
+#[test]
+fn cmp() {
+    let s = ByteStr::from_static("b");
+
+    assert!(s > "a");
+    assert!("c" > s);
+    assert!(s < String::from("c"));
+    assert_eq!(String::from("b"), s);
+    assert!(ByteStr::from("a") < s);
+}
@@ -1,6 +1,7 @@
 #![deny(warnings, rust_2018_idioms)]
 
-use bytes::{Bytes, BytesMut, Buf, BufMut};
+use bytes::{BoundsError, Bytes, BytesMut, Buf, BufMut, TryReserveError, WeakBytes};
+use bytes::encoding::{Base64, DecodeError};
 
 const LONG: &'static [u8] = b"mary had a little lamb, little lamb, little lamb";
 const SHORT: &'static [u8] = b"hello world";
@@ -19,6 +20,8 @@ fn test_bounds() {
     is_sync::<BytesMut>();
     is_send::<Bytes>();
     is_send::<BytesMut>();
+    is_sync::<WeakBytes>();
+    is_send::<WeakBytes>();
 }
 
 #[test]
@@ -424,6 +427,68 @@ fn reserve_in_arc_nonunique_does_not_overallocate() {
     assert_eq!(2001, bytes.capacity());
 }
 
+#[test]
+fn try_reserve_capacity_overflow() {
+    let mut inline = BytesMut::from(&b"hello"[..]);
+    let mut vec = BytesMut::from(LONG);
+    let mut arc = BytesMut::from(LONG);
+    let _other = arc.split_off(10);
+
+    for buf in &mut [inline.clone(), vec.clone(), arc.clone()] {
+        let expect = buf.clone();
+        assert_eq!(buf.try_reserve(usize::MAX), Err(TryReserveError::CapacityOverflow));
+        assert_eq!(buf.try_reserve_exact(usize::MAX), Err(TryReserveError::CapacityOverflow));
+        assert_eq!(*buf, expect);
+    }
+
+    assert!(inline.try_reserve(isize::MAX as usize).is_err());
+    assert!(vec.try_reserve(isize::MAX as usize).is_err());
+    assert!(arc.try_reserve(isize::MAX as usize).is_err());
+    assert_eq!(inline, b"hello"[..]);
+    assert_eq!(vec, LONG);
+    assert_eq!(arc, LONG[..10]);
+}
+
+#[test]
+fn try_with_capacity_alloc_error() {
+    let cap = isize::MAX as usize;
+
+    match BytesMut::try_with_capacity(cap) {
+        Err(TryReserveError::AllocError { layout }) => assert_eq!(layout.size(), cap),
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    assert_eq!(BytesMut::try_with_capacity(inline_cap()).unwrap().capacity(), inline_cap());
+    assert_eq!(BytesMut::try_with_capacity(1000).unwrap().capacity(), 1000);
+}
+
+#[test]
+fn try_reserve_exact_in_arc_unique() {
+    let mut bytes = BytesMut::with_capacity(1000);
+    bytes.split();
+
+    // now bytes is Arc and refcount == 1
+
+    bytes.try_reserve_exact(1001).unwrap();
+    assert_eq!(1001, bytes.capacity());
+
+    let mut bytes = BytesMut::with_capacity(1000);
+    bytes.split();
+
+    bytes.try_reserve(1001).unwrap();
+    assert_eq!(2000, bytes.capacity());
+}
+
+#[test]
+fn try_extend_from_slice() {
+    let mut bytes = BytesMut::with_capacity(0);
+    bytes.try_extend_from_slice(LONG).unwrap();
+    bytes.try_extend_from_slice(SHORT).unwrap();
+
+    assert_eq!(&bytes[..LONG.len()], LONG);
+    assert_eq!(&bytes[LONG.len()..], SHORT);
+}
+
 #[test]
 fn inline_storage() {
     let mut bytes = BytesMut::with_capacity(inline_cap());
@@ -893,3 +958,713 @@ fn empty_slice_ref_catches_not_an_empty_subset() {
 
     bytes.slice_ref(slice);
 }
+
+struct Owner {
+    data: Vec<u8>,
+    drops: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+}
+
+impl AsRef<[u8]> for Owner {
+    fn as_ref(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+impl Drop for Owner {
+    fn drop(&mut self) {
+        self.drops.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+fn owner(data: &[u8]) -> (Owner, std::sync::Arc<std::sync::atomic::AtomicUsize>) {
+    let drops = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let owner = Owner {
+        data: data.to_vec(),
+        drops: drops.clone(),
+    };
+    (owner, drops)
+}
+
+#[test]
+fn from_owner() {
+    use std::sync::atomic::Ordering::SeqCst;
+
+    let (o, drops) = owner(LONG);
+    let ptr = o.data.as_ptr();
+
+    let mut a = Bytes::from_owner(o);
+    assert_eq!(a, LONG);
+    assert_eq!(a.as_ptr(), ptr);
+    assert!(!a.is_inline());
+
+    let b = a.clone();
+    assert_eq!(b.as_ptr(), ptr);
+
+    let c = a.slice(5..45);
+    assert_eq!(c, LONG[5..45]);
+    assert_eq!(c.as_ptr(), unsafe { ptr.offset(5) });
+
+    let d = a.split_to(40);
+    assert_eq!(d, LONG[..40]);
+    assert_eq!(a, LONG[40..]);
+    assert_eq!(d.as_ptr(), ptr);
+
+    drop(a);
+    drop(b);
+    drop(c);
+    assert_eq!(drops.load(SeqCst), 0);
+
+    drop(d);
+    assert_eq!(drops.load(SeqCst), 1);
+}
+
+#[test]
+fn from_owner_is_never_mutable() {
+    use std::sync::atomic::Ordering::SeqCst;
+
+    let (o, drops) = owner(LONG);
+    let ptr = o.data.as_ptr();
+
+    // Even the only handle may not mutate the owner's memory.
+    let a = Bytes::from_owner(o);
+    let mut a = a.try_mut().unwrap_err();
+
+    a.make_mut()[0] = b'M';
+    assert_eq!(a[0], b'M');
+    assert_ne!(a.as_ptr(), ptr);
+    assert_eq!(drops.load(SeqCst), 1);
+
+    let (o, _) = owner(LONG);
+    let mut b = Bytes::from_owner(o);
+    b.extend_from_slice(b"!");
+    assert_eq!(&b[..LONG.len()], LONG);
+    assert_eq!(b[LONG.len()], b'!');
+}
+
+#[test]
+fn from_owner_unsplit_adjacent_owners() {
+    static DATA: &[u8] = b"mary had a little lamb, little lamb, little lamb";
+
+    // Two owners referencing adjacent memory must not be merged.
+    let mut a = Bytes::from_owner(&DATA[..30]);
+    let b = Bytes::from_owner(&DATA[30..]);
+
+    a.unsplit(b);
+    assert_eq!(a, DATA);
+    assert_ne!(a.as_ptr(), DATA.as_ptr());
+}
+
+#[test]
+fn downgrade_arc() {
+    let data: Vec<u8> = (0..100).collect();
+
+    let mut a = Bytes::from(&data[..]);
+    let b = a.split_off(10);
+
+    let weak = b.slice(5..60).downgrade();
+    assert_eq!(weak.upgrade().unwrap(), data[15..70]);
+
+    drop(b);
+    assert_eq!(weak.upgrade().unwrap(), data[15..70]);
+    assert_eq!(weak.clone().upgrade().unwrap(), data[15..70]);
+
+    drop(a);
+    assert!(weak.upgrade().is_none());
+    assert!(weak.clone().upgrade().is_none());
+}
+
+#[test]
+fn downgrade_vec() {
+    let a = Bytes::from(LONG.to_vec());
+    let ptr = a.as_ptr();
+
+    let weak = a.downgrade();
+
+    let b = weak.upgrade().unwrap();
+    assert_eq!(b, LONG);
+    assert_eq!(b.as_ptr(), ptr);
+
+    drop(a);
+    drop(b);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn downgrade_inline_and_static() {
+    let a = Bytes::from(SHORT);
+    assert!(a.is_inline());
+
+    let weak = a.downgrade();
+    drop(a);
+    assert_eq!(weak.upgrade().unwrap(), SHORT);
+
+    let a = Bytes::from_static(LONG);
+    let weak = a.downgrade();
+    drop(a);
+    assert_eq!(weak.upgrade().unwrap(), LONG);
+    assert_eq!(weak.upgrade().unwrap().as_ptr(), LONG.as_ptr());
+}
+
+#[test]
+fn downgrade_prevents_mutation() {
+    let a = Bytes::from(LONG.to_vec());
+    let weak = a.downgrade();
+
+    // A weak handle could be upgraded at any time, so the memory may not be
+    // mutated in place.
+    let a = a.try_mut().unwrap_err();
+    assert_eq!(weak.upgrade().unwrap(), LONG);
+
+    drop(weak);
+    assert!(a.try_mut().is_ok());
+}
+
+#[test]
+fn downgrade_releases_owner() {
+    use std::sync::atomic::Ordering::SeqCst;
+
+    let (o, drops) = owner(LONG);
+    let a = Bytes::from_owner(o);
+    let weak = a.downgrade();
+
+    drop(a);
+    assert_eq!(drops.load(SeqCst), 1);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn make_mut_vec_no_copy() {
+    let mut a = Bytes::from(LONG.to_vec());
+    let ptr = a.as_ptr();
+
+    a.make_mut()[0] = b'M';
+    assert_eq!(a.as_ptr(), ptr);
+    assert_eq!(&a[1..], &LONG[1..]);
+    assert_eq!(a[0], b'M');
+}
+
+#[test]
+fn make_mut_unique_arc_no_copy() {
+    let mut a = Bytes::from(LONG.to_vec());
+    let b = a.clone();
+    let ptr = a.as_ptr();
+
+    // Dropping the other handle leaves `a` as the only reference
+    drop(b);
+    a.make_mut()[0] = b'M';
+    assert_eq!(a.as_ptr(), ptr);
+    assert_eq!(&a[1..], &LONG[1..]);
+}
+
+#[test]
+fn make_mut_shared_copies_window() {
+    let a = Bytes::from(LONG.to_vec());
+    let mut b = a.slice(4..44);
+
+    b.make_mut()[0] = b'X';
+    assert_eq!(&a[..], LONG);
+    assert_eq!(b.len(), 40);
+    assert_eq!(b[0], b'X');
+    assert_eq!(&b[1..], &LONG[5..44]);
+
+    // Only the window was copied
+    assert!(b.make_mut().capacity() < LONG.len());
+}
+
+#[test]
+fn make_mut_static() {
+    let mut a = Bytes::from_static(LONG);
+
+    a.make_mut()[0] = b'M';
+    assert_ne!(a.as_ptr(), LONG.as_ptr());
+    assert_eq!(&a[1..], &LONG[1..]);
+}
+
+#[test]
+fn into_mut() {
+    let a = Bytes::from(LONG.to_vec());
+    let ptr = a.as_ptr();
+    let b = a.into_mut();
+    assert_eq!(b.as_ptr(), ptr);
+    assert_eq!(&b[..], LONG);
+
+    let a = Bytes::from(LONG.to_vec());
+    let c = a.clone();
+    let b = a.into_mut();
+    assert_ne!(b.as_ptr(), c.as_ptr());
+    assert_eq!(&b[..], LONG);
+
+    let b = Bytes::from_static(LONG).into_mut();
+    assert_eq!(&b[..], LONG);
+}
+
+#[test]
+fn copy_to_bytes() {
+    let mut bytes = Bytes::from(LONG.to_vec());
+    let ptr = bytes.as_ptr();
+
+    let a = bytes.copy_to_bytes(40);
+    assert_eq!(a.as_ptr(), ptr);
+    assert_eq!(&a[..], &LONG[..40]);
+    assert_eq!(&bytes[..], &LONG[40..]);
+
+    let mut bytes = BytesMut::from(LONG);
+    let ptr = bytes.as_ptr();
+
+    let a = bytes.copy_to_bytes(40);
+    assert_eq!(a.as_ptr(), ptr);
+    assert_eq!(&bytes[..], &LONG[40..]);
+}
+
+#[test]
+#[should_panic]
+fn copy_to_bytes_overflow() {
+    let mut bytes = Bytes::from(&b"hello"[..]);
+    bytes.copy_to_bytes(6);
+}
+
+#[test]
+fn collect_bytes_is_zero_copy() {
+    let bytes = Bytes::from(LONG.to_vec());
+    let ptr = bytes.as_ptr();
+
+    let collected: Bytes = bytes.collect();
+    assert_eq!(collected.as_ptr(), ptr);
+    assert_eq!(&collected[..], LONG);
+}
+
+#[test]
+fn len_slot_fixed() {
+    use bytes::buf::LenPrefix;
+
+    let mut buf = BytesMut::new();
+    buf.extend_from_slice(b"head");
+
+    let slot = buf.put_len_slot(LenPrefix::U16Le);
+    buf.extend_from_slice(LONG);
+    buf.fill_len_slot(slot);
+
+    assert_eq!(&buf[..6], b"head\x30\x00");
+    assert_eq!(&buf[6..], LONG);
+
+    let slot = buf.put_len_slot(LenPrefix::U64Be);
+    buf.fill_len_slot(slot);
+    assert_eq!(&buf[6 + LONG.len()..], &[0; 8][..]);
+}
+
+#[test]
+fn len_slot_varint_shifts_body() {
+    use bytes::buf::LenPrefix;
+
+    let mut buf = BytesMut::new();
+
+    let slot = buf.put_len_slot(LenPrefix::Varint);
+    buf.extend_from_slice(LONG);
+    buf.extend_from_slice(LONG);
+    buf.extend_from_slice(LONG);
+    buf.fill_len_slot(slot);
+
+    let mut frozen = buf.freeze();
+    assert_eq!(frozen.get_uvarint(), Ok(3 * LONG.len() as u64));
+    assert_eq!(&frozen[..LONG.len()], LONG);
+    assert_eq!(&frozen[2 * LONG.len()..], LONG);
+}
+
+#[test]
+fn put_len_prefixed_nested() {
+    use bytes::buf::LenPrefix;
+
+    let mut buf = BytesMut::new();
+
+    let n = buf.put_len_prefixed(LenPrefix::U32Be, |outer| {
+        outer.put_len_prefixed(LenPrefix::Varint, |inner| {
+            inner.extend_from_slice(&[1; 130]);
+        });
+        outer.put_u8(2);
+        42
+    });
+
+    assert_eq!(n, 42);
+    assert_eq!(&buf[..6], b"\x00\x00\x00\x85\x82\x01");
+    assert_eq!(buf.len(), 4 + 2 + 130 + 1);
+    assert_eq!(buf[buf.len() - 1], 2);
+}
+
+#[test]
+#[should_panic]
+fn len_slot_too_long() {
+    use bytes::buf::LenPrefix;
+
+    let mut buf = BytesMut::new();
+    buf.put_len_prefixed(LenPrefix::U8, |body| body.extend_from_slice(&[0; 256]));
+}
+
+#[test]
+fn get_set_at() {
+    let mut buf = BytesMut::from(&[0; 16][..]);
+
+    buf.set_u16_at(0, 0x0102);
+    buf.set_u32_le_at(2, 0x03040506);
+    buf.set_i64_at(8, -2);
+
+    assert_eq!(&buf[..6], b"\x01\x02\x06\x05\x04\x03");
+    assert_eq!(buf.get_u16_le_at(0), 0x0201);
+    assert_eq!(buf.get_u32_at(2), 0x06050403);
+    assert_eq!(buf.get_i64_at(8), -2);
+    assert_eq!(buf.len(), 16);
+
+    buf.set_f32_le_at(12, 1.5);
+    let frozen = buf.freeze();
+    assert_eq!(frozen.get_f32_le_at(12), 1.5);
+    assert_eq!(frozen.get_u8_at(15), 0x3f);
+}
+
+#[test]
+fn try_get_set_at_out_of_bounds() {
+    let mut buf = BytesMut::from(&[0; 8][..]);
+
+    assert_eq!(buf.try_get_u32_at(6), Err(BoundsError { offset: 6, size: 4, len: 8 }));
+    assert_eq!(buf.try_get_u8_at(9), Err(BoundsError { offset: 9, size: 1, len: 8 }));
+    assert_eq!(buf.try_get_u64_at(usize::MAX), Err(BoundsError { offset: usize::MAX, size: 8, len: 8 }));
+
+    let err = buf.try_set_u16_at(7, 0xffff).unwrap_err();
+    assert_eq!(err, BoundsError { offset: 7, size: 2, len: 8 });
+    assert_eq!(err.to_string(), "range 7..9 is out of bounds for length 8");
+    assert_eq!(&buf[..], &[0; 8][..]);
+    assert_eq!(buf.try_set_u64_le_at(0, 1), Ok(()));
+    assert_eq!(Bytes::from(buf).try_get_u64_le_at(0), Ok(1));
+}
+
+#[test]
+#[should_panic]
+fn get_at_out_of_bounds() {
+    let buf = Bytes::from(&b"abc"[..]);
+    buf.get_u32_at(0);
+}
+
+#[test]
+#[should_panic(expected = "range 2..4 is out of bounds for length 3")]
+fn set_at_out_of_bounds() {
+    let mut buf = BytesMut::from(&b"abc"[..]);
+    buf.set_u16_at(2, 0);
+}
+
+#[test]
+fn with_headroom() {
+    let mut buf = BytesMut::with_headroom(16, 64);
+    assert_eq!(buf.headroom(), 16);
+    assert!(buf.capacity() >= 64);
+    assert!(!buf.is_inline());
+
+    buf.extend_from_slice(b"payload");
+    let ptr = buf.as_ptr();
+
+    buf.put_u32_front(7);
+    buf.put_u16_le_front(0x0102);
+    buf.push_front(0xff);
+
+    assert_eq!(&buf[..], b"\xff\x02\x01\x00\x00\x00\x07payload");
+    assert_eq!(buf[7..].as_ptr(), ptr);
+    assert_eq!(buf.headroom(), 9);
+}
+
+#[test]
+fn prepend_reallocates() {
+    let mut buf = BytesMut::from(&b"world"[..]);
+    assert_eq!(buf.headroom(), 0);
+
+    buf.prepend_slice(b"hello ");
+    assert_eq!(&buf[..], b"hello world");
+
+    let mut buf = BytesMut::with_headroom(2, 16);
+    buf.extend_from_slice(b"c");
+    buf.prepend_slice(b"ab");
+    assert_eq!(buf.headroom(), 0);
+
+    buf.put_i64_front(-1);
+    assert_eq!(&buf[..8], &[0xff; 8][..]);
+    assert_eq!(&buf[8..], b"abc");
+}
+
+#[test]
+fn headroom_after_split() {
+    let mut buf = BytesMut::with_headroom(8, 64);
+    buf.extend_from_slice(LONG);
+
+    // The space in front of `buf` now belongs to `head`
+    let head = buf.split_to(10);
+    assert_eq!(buf.headroom(), 0);
+    assert_eq!(head.headroom(), 0);
+
+    drop(head);
+    assert_eq!(buf.headroom(), 18);
+
+    let ptr = buf.as_ptr();
+    buf.prepend_slice(b"xyz");
+    assert_eq!(buf[3..].as_ptr(), ptr);
+    assert_eq!(&buf[3..], &LONG[10..]);
+}
+
+#[test]
+fn headroom_after_freeze_and_reserve() {
+    let mut buf = BytesMut::with_headroom(8, 16);
+    buf.extend_from_slice(b"body");
+
+    let bytes = buf.freeze();
+    let mut buf = bytes.try_mut().unwrap();
+    assert_eq!(buf.headroom(), 8);
+
+    // Growing the buffer keeps the headroom
+    buf.extend_from_slice(LONG);
+    buf.extend_from_slice(LONG);
+    assert_eq!(buf.headroom(), 8);
+
+    buf.prepend_slice(b"head");
+    assert_eq!(&buf[..8], b"headbody");
+    assert_eq!(buf.len(), 8 + 2 * LONG.len());
+}
+
+#[test]
+fn prepend_amortized() {
+    let mut buf = BytesMut::new();
+    let mut reallocs = 0;
+
+    for i in 0..100 {
+        let ptr = buf.as_ptr();
+        buf.push_front(i);
+
+        if buf[1..].as_ptr() != ptr {
+            reallocs += 1;
+        }
+    }
+
+    assert!(reallocs <= 8, "reallocated {} times", reallocs);
+    assert_eq!(buf.len(), 100);
+    assert_eq!(buf[0], 99);
+    assert_eq!(buf[99], 0);
+}
+
+#[test]
+fn headroom_after_reserve_unique_arc() {
+    let mut buf = BytesMut::with_headroom(8, 16);
+    buf.extend_from_slice(b"body");
+
+    // Promote to a shared buffer, with the capacity of `buf` cut at 4 bytes
+    drop(buf.split_off(4));
+    assert_eq!(buf.headroom(), 8);
+
+    // There is room after the data, so it stays in place
+    let ptr = buf.as_ptr();
+    buf.extend_from_slice(b"0123456789");
+    assert_eq!(buf.as_ptr(), ptr);
+    assert_eq!(buf.headroom(), 8);
+
+    // Reallocating keeps the headroom
+    buf.extend_from_slice(LONG);
+    assert_eq!(buf.headroom(), 8);
+
+    buf.prepend_slice(b"head");
+    assert_eq!(&buf[..18], b"headbody0123456789");
+    assert_eq!(&buf[18..], LONG);
+}
+
+#[test]
+fn headroom_reused_by_reserve() {
+    // The headroom is much larger than the capacity after the data, so
+    // growing the buffer moves the data to the front instead of reallocating.
+    let mut buf = BytesMut::with_headroom(128, 100);
+    let start = buf.as_ptr() as usize - 128;
+
+    buf.extend_from_slice(&[0; 101][..]);
+    assert_eq!(buf.headroom(), 0);
+    assert_eq!(buf.as_ptr() as usize, start);
+
+    // Reserving capacity up front keeps the headroom
+    let mut buf = BytesMut::with_headroom(128, 256);
+    buf.extend_from_slice(&[0; 101][..]);
+    buf.extend_from_slice(&[0; 101][..]);
+    assert_eq!(buf.headroom(), 128);
+}
+
+#[test]
+fn find() {
+    let buf = Bytes::from(LONG);
+
+    assert_eq!(buf.find(b"lamb"), Some(18));
+    assert_eq!(buf.rfind(b"lamb"), Some(44));
+    assert_eq!(buf.find(b"m"), Some(0));
+    assert_eq!(buf.rfind(b"m"), Some(46));
+    assert_eq!(buf.find(b"goat"), None);
+    assert_eq!(buf.find(b""), Some(0));
+    assert_eq!(buf.rfind(b""), Some(LONG.len()));
+    assert_eq!(Bytes::new().find(b"a"), None);
+}
+
+#[test]
+fn split_once() {
+    let buf = Bytes::from(LONG);
+
+    let (head, tail) = buf.split_once(b", ").unwrap();
+    assert_eq!(head, LONG[..22]);
+    assert_eq!(tail, LONG[24..]);
+
+    // Pieces too long to be inlined reference the original buffer
+    let (_, tail) = buf.split_once(b"had").unwrap();
+    assert_eq!(tail, LONG[8..]);
+    assert_eq!(tail.as_ptr(), buf[8..].as_ptr());
+
+    assert!(buf.split_once(b"goat").is_none());
+}
+
+#[test]
+#[should_panic]
+fn split_once_empty_delim() {
+    Bytes::from(LONG).split_once(b"");
+}
+
+#[test]
+fn split() {
+    let parts: Vec<Bytes> = Bytes::from(LONG).split(b"lamb").collect();
+    assert_eq!(parts, ["mary had a little ", ", little ", ", little ", ""]);
+
+    let parts: Vec<Bytes> = Bytes::from_static(b",a,,b,").split(b",").collect();
+    assert_eq!(parts, ["", "a", "", "b", ""]);
+
+    let parts: Vec<Bytes> = Bytes::new().split(b",").collect();
+    assert_eq!(parts, [""]);
+}
+
+#[test]
+fn lines() {
+    let buf = Bytes::from_static(b"one\ntwo\r\n\nthree\r");
+    let lines: Vec<Bytes> = buf.lines().collect();
+    assert_eq!(lines, ["one", "two", "", "three"]);
+
+    let lines: Vec<Bytes> = Bytes::from_static(b"one\n").lines().collect();
+    assert_eq!(lines, ["one"]);
+
+    assert_eq!(Bytes::new().lines().count(), 0);
+}
+
+#[test]
+fn chunks() {
+    let buf = Bytes::from(LONG);
+
+    let chunks: Vec<Bytes> = buf.chunks(20).collect();
+    assert_eq!(chunks, [&LONG[..20], &LONG[20..40], &LONG[40..]]);
+
+    // The chunks are never copied, even when small
+    for (i, chunk) in buf.chunks(20).enumerate() {
+        assert_eq!(chunk.as_ptr(), buf[i * 20..].as_ptr());
+    }
+
+    let mut iter = buf.chunks(20);
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.next_back().unwrap(), LONG[40..]);
+    assert_eq!(iter.len(), 2);
+    assert_eq!(iter.next_back().unwrap(), LONG[20..40]);
+    assert_eq!(iter.next().unwrap(), LONG[..20]);
+    assert!(iter.next().is_none());
+    assert!(iter.next_back().is_none());
+
+    let chunks: Vec<Bytes> = buf.chunks(16).rev().collect();
+    assert_eq!(chunks, [&LONG[32..], &LONG[16..32], &LONG[..16]]);
+
+    assert_eq!(Bytes::new().chunks(4).len(), 0);
+    assert_eq!(buf.chunks(100).len(), 1);
+}
+
+#[test]
+#[should_panic]
+fn chunks_zero_size() {
+    Bytes::from(LONG).chunks(0);
+}
+
+#[test]
+fn chunks_exact() {
+    let buf = Bytes::from(LONG);
+
+    let mut iter = buf.chunks_exact(20);
+    assert_eq!(iter.len(), 2);
+    assert_eq!(iter.remainder(), &LONG[40..]);
+    assert_eq!(iter.next_back().unwrap(), LONG[20..40]);
+    assert_eq!(iter.next().unwrap(), LONG[..20]);
+    assert!(iter.next().is_none());
+    assert_eq!(iter.remainder(), &LONG[40..]);
+
+    let mut iter = buf.chunks_exact(16);
+    assert_eq!(iter.len(), 3);
+    assert!(iter.remainder().is_empty());
+    assert_eq!(iter.nth(2).unwrap(), LONG[32..]);
+}
+
+#[test]
+fn rchunks() {
+    let buf = Bytes::from(LONG);
+
+    let chunks: Vec<Bytes> = buf.rchunks(20).collect();
+    assert_eq!(chunks, [&LONG[28..], &LONG[8..28], &LONG[..8]]);
+
+    let mut iter = buf.rchunks(20);
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.next_back().unwrap(), LONG[..8]);
+    assert_eq!(iter.next_back().unwrap(), LONG[8..28]);
+    assert_eq!(iter.len(), 1);
+    assert_eq!(iter.next().unwrap(), LONG[28..]);
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn split_into_chunks() {
+    let buf = BytesMut::from(LONG);
+
+    let mut chunks: Vec<BytesMut> = buf.split_into_chunks(20).collect();
+    assert_eq!(chunks, [&LONG[..20], &LONG[20..40], &LONG[40..]]);
+
+    // The chunks are independent
+    chunks[0].extend_from_slice(b"!");
+    chunks[1][0] = b'L';
+    assert_eq!(chunks[0], b"mary had a little la!"[..]);
+    assert_eq!(chunks[1], b"Lb, little lamb, lit"[..]);
+
+    let chunks: Vec<BytesMut> = BytesMut::from(LONG).split_into_chunks(16).rev().collect();
+    assert_eq!(chunks, [&LONG[32..], &LONG[16..32], &LONG[..16]]);
+}
+
+#[test]
+fn fmt_hex() {
+    let buf = Bytes::from_static(b"\x00\x0f\xab\xff");
+
+    assert_eq!(format!("{:x}", buf), "000fabff");
+    assert_eq!(format!("{:X}", buf), "000FABFF");
+    assert_eq!(format!("{:#x}", buf), "0x000fabff");
+    assert_eq!(format!("{:x}", BytesMut::from(&b"\x12\x34"[..])), "1234");
+    assert_eq!(format!("{:X}", BytesMut::new()), "");
+
+    // Longer than the internal staging buffer
+    let long = Bytes::from(vec![0xa5; 200]);
+    assert_eq!(format!("{:x}", long), "a5".repeat(200));
+}
+
+#[test]
+fn from_hex() {
+    assert_eq!(Bytes::from_hex("000fABff").unwrap(), &b"\x00\x0f\xab\xff"[..]);
+    assert_eq!(Bytes::from_hex(b"").unwrap(), Bytes::new());
+    assert_eq!(Bytes::from_hex("0").unwrap_err(), DecodeError::InvalidLength);
+    assert_eq!(Bytes::from_hex("0g").unwrap_err(), DecodeError::InvalidByte { index: 1, byte: b'g' });
+
+    let long = "a5".repeat(300);
+    assert_eq!(Bytes::from_hex(&long).unwrap(), vec![0xa5; 300]);
+}
+
+#[test]
+fn from_base64() {
+    assert_eq!(Bytes::from_base64("aGVsbG8=", Base64::Standard).unwrap(), &b"hello"[..]);
+    assert_eq!(Bytes::from_base64("aGVsbG8", Base64::Standard).unwrap(), &b"hello"[..]);
+    assert_eq!(Bytes::from_base64("-_8", Base64::UrlSafeNoPad).unwrap(), &b"\xfb\xff"[..]);
+    assert_eq!(
+        Bytes::from_base64("+/8=", Base64::UrlSafe).unwrap_err(),
+        DecodeError::InvalidByte { index: 0, byte: b'+' },
+    );
+    assert_eq!(Bytes::from_base64("aGVsbG8==", Base64::Standard).unwrap_err(), DecodeError::InvalidLength);
+}
@@ -2,6 +2,7 @@
 
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 use bytes::buf::Chain;
+#[cfg(feature = "std")]
 use std::io::IoSlice;
 
 #[test]
@@ -46,6 +47,7 @@ fn iterating_two_bufs() {
 }
 
 #[test]
+#[cfg(feature = "std")]
 fn vectored_read() {
     let a = Bytes::from(&b"hello"[..]);
     let b = Bytes::from(&b"world"[..]);
@@ -134,3 +136,52 @@ fn vectored_read() {
         assert_eq!(iovecs[3][..], b""[..]);
     }
 }
+
+#[test]
+fn peek_across_bufs() {
+    let a = Bytes::from(&b"\x01\x02\x03"[..]);
+    let b = Bytes::from(&b"\x04\x05"[..]);
+    let buf = a.chain(b);
+
+    assert_eq!(0x01, buf.peek_u8());
+    assert_eq!(0x04030201, buf.peek_u32_le());
+
+    let mut dst = [0; 5];
+    buf.peek_slice(&mut dst);
+    assert_eq!(&dst, b"\x01\x02\x03\x04\x05");
+    assert_eq!(buf.remaining(), 5);
+}
+
+#[test]
+fn varint_across_bufs() {
+    let a = Bytes::from(&b"\x01\xff\xff"[..]);
+    let b = Bytes::from(&b"\x03\x80"[..]);
+    let mut buf = a.chain(b);
+
+    assert_eq!(Ok(1), buf.get_uvarint());
+    assert_eq!(Ok(0xffff), buf.get_uvarint());
+    assert!(buf.get_uvarint().is_err());
+    assert_eq!(buf.remaining(), 1);
+}
+
+#[test]
+fn copy_to_bytes() {
+    let a = Bytes::from(&[1; 64][..]);
+    let b = Bytes::from(&[2; 64][..]);
+    let (a_ptr, b_ptr) = (a.as_ptr(), b.as_ptr());
+    let mut buf = a.chain(b);
+
+    // Within the first buffer
+    let first = buf.copy_to_bytes(40);
+    assert_eq!(first.as_ptr(), a_ptr);
+
+    // Straddles both buffers, so the data is copied
+    let mid = buf.copy_to_bytes(48);
+    assert_eq!(&mid[..24], &[1; 24][..]);
+    assert_eq!(&mid[24..], &[2; 24][..]);
+
+    // Within the second buffer
+    let last = buf.copy_to_bytes(40);
+    assert_eq!(last.as_ptr(), b_ptr.wrapping_add(24));
+    assert_eq!(buf.remaining(), 0);
+}
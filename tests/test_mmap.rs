@@ -0,0 +1,109 @@
+#![cfg(feature = "mmap")]
+#![deny(warnings, rust_2018_idioms)]
+
+use bytes::Bytes;
+use std::fs::{self, File};
+use std::io::Write;
+use std::ops::Range;
+use std::path::PathBuf;
+
+const LONG: &'static [u8] = b"mary had a little lamb, little lamb, little lamb";
+
+struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    fn new(name: &str, contents: &[u8]) -> TempFile {
+        let path = std::env::temp_dir()
+            .join(format!("bytes-test-mmap-{}-{}", std::process::id(), name));
+
+        File::create(&path).unwrap().write_all(contents).unwrap();
+
+        TempFile { path: path }
+    }
+
+    fn open(&self) -> File {
+        File::open(&self.path).unwrap()
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[test]
+fn map_file() {
+    let tmp = TempFile::new("map_file", LONG);
+
+    let mut a = unsafe { Bytes::map_file(&tmp.open()).unwrap() };
+    assert_eq!(a, LONG);
+
+    let ptr = a.as_ptr();
+
+    let b = a.clone();
+    assert_eq!(b.as_ptr(), ptr);
+
+    let c = a.slice(5..45);
+    assert_eq!(c, LONG[5..45]);
+    assert_eq!(c.as_ptr(), unsafe { ptr.offset(5) });
+
+    let d = a.split_off(8);
+    assert_eq!(a, LONG[..8]);
+    assert_eq!(d, LONG[8..]);
+    assert_eq!(d.as_ptr(), unsafe { ptr.offset(8) });
+
+    // The mapping outlives the original handle.
+    drop(a);
+    drop(b);
+    drop(c);
+    assert_eq!(d, LONG[8..]);
+}
+
+#[test]
+fn map_range() {
+    let tmp = TempFile::new("map_range", LONG);
+
+    let a = unsafe { Bytes::map_range(&tmp.open(), 11..40).unwrap() };
+    assert_eq!(a, LONG[11..40]);
+    assert!(a.try_mut().is_err());
+}
+
+#[test]
+fn map_empty() {
+    let tmp = TempFile::new("map_empty", b"");
+
+    let a = unsafe { Bytes::map_file(&tmp.open()).unwrap() };
+    assert!(a.is_empty());
+
+    let tmp = TempFile::new("map_empty_range", LONG);
+
+    let a = unsafe { Bytes::map_range(&tmp.open(), 10..10).unwrap() };
+    assert!(a.is_empty());
+}
+
+#[test]
+fn map_invalid_range() {
+    let tmp = TempFile::new("map_invalid_range", LONG);
+
+    let range = Range { start: 10, end: 5 };
+    let res = unsafe { Bytes::map_range(&tmp.open(), range) };
+    assert!(res.is_err());
+}
+
+#[test]
+fn map_range_past_end() {
+    let tmp = TempFile::new("map_range_past_end", LONG);
+    let len = LONG.len() as u64;
+
+    let err = unsafe { Bytes::map_range(&tmp.open(), 10..len + 1).unwrap_err() };
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+    let err = unsafe { Bytes::map_range(&tmp.open(), len + 1..len + 1).unwrap_err() };
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+    let a = unsafe { Bytes::map_range(&tmp.open(), 10..len).unwrap() };
+    assert_eq!(a, LONG[10..]);
+}
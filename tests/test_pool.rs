@@ -0,0 +1,127 @@
+#![cfg(feature = "std")]
+#![deny(warnings, rust_2018_idioms)]
+
+use bytes::{Buf, BufMut, BytesPool, PoolStats};
+
+use std::thread;
+
+fn is_sync<T: Sync>() {}
+fn is_send<T: Send>() {}
+
+#[test]
+fn test_bounds() {
+    is_sync::<BytesPool>();
+    is_send::<BytesPool>();
+}
+
+#[test]
+fn size_classes() {
+    let pool = BytesPool::new(&[4096, 0, 512, 4096], 1 << 20);
+    assert_eq!(pool.size_classes(), &[512, 4096]);
+
+    assert_eq!(pool.get(0).capacity(), 512);
+    assert_eq!(pool.get(512).capacity(), 512);
+    assert_eq!(pool.get(513).capacity(), 4096);
+
+    // Larger than every class
+    assert_eq!(pool.get(5000).capacity(), 5000);
+
+    let stats = pool.stats();
+    assert_eq!(stats.hits, 1);
+    assert_eq!(stats.misses, 3);
+    assert_eq!(stats.returned, 3);
+}
+
+#[test]
+fn reuse_allocation() {
+    let pool = BytesPool::new(&[1024], 1 << 20);
+
+    let mut buf = pool.get(100);
+    let ptr = buf.as_ptr();
+    buf.put(&b"hello world"[..]);
+    drop(buf);
+
+    let buf = pool.get(100);
+    assert!(buf.is_empty());
+    assert_eq!(buf.capacity(), 1024);
+    assert_eq!(buf.as_ptr(), ptr);
+
+    assert_eq!(pool.stats(), PoolStats {
+        hits: 1,
+        misses: 1,
+        returned: 1,
+        discarded: 0,
+        retained_bytes: 0,
+    });
+}
+
+#[test]
+fn returned_after_last_handle() {
+    let pool = BytesPool::new(&[1024], 1 << 20);
+
+    let mut buf = pool.get(1024);
+    buf.put(&[0; 100][..]);
+
+    let a = buf.split_to(50).freeze();
+    let b = a.clone();
+    let weak = a.downgrade();
+
+    drop(buf);
+    drop(a);
+    assert_eq!(pool.stats().returned, 0);
+
+    drop(b);
+    assert!(weak.upgrade().is_none());
+    assert_eq!(pool.stats().returned, 1);
+    assert_eq!(pool.stats().retained_bytes, 1024);
+}
+
+#[test]
+fn max_retained() {
+    let pool = BytesPool::new(&[1024], 2048);
+
+    let bufs: Vec<_> = (0..3).map(|_| pool.get(1024)).collect();
+    drop(bufs);
+
+    let stats = pool.stats();
+    assert_eq!(stats.returned, 2);
+    assert_eq!(stats.discarded, 1);
+    assert_eq!(stats.retained_bytes, 2048);
+
+    pool.clear();
+    assert_eq!(pool.stats().retained_bytes, 0);
+
+    drop(pool.get(1024));
+    assert_eq!(pool.stats().hits, 0);
+}
+
+#[test]
+fn reserve_in_pooled_buffer() {
+    let pool = BytesPool::new(&[64], 1 << 20);
+
+    // Reclaiming the unique allocation keeps it pooled
+    let mut buf = pool.get(64);
+    buf.put(&[1; 64][..]);
+    buf.advance(32);
+    buf.reserve(32);
+    assert_eq!(buf.capacity(), 64);
+    assert_eq!(pool.stats().returned, 0);
+
+    // Growing moves the data into a new allocation and returns the old one
+    buf.reserve(1000);
+    assert_eq!(&buf[..], &[1; 32][..]);
+    assert_eq!(pool.stats().returned, 1);
+}
+
+#[test]
+fn outlives_pool_handle() {
+    let pool = BytesPool::new(&[1024], 1 << 20);
+    let buf = pool.get(1024).freeze();
+    drop(pool);
+
+    let th = thread::spawn(move || {
+        assert!(buf.is_empty());
+    });
+
+    th.join().unwrap();
+}
@@ -1,3 +1,4 @@
+#![cfg(feature = "std")]
 #![deny(warnings, rust_2018_idioms)]
 
 use std::io::{BufRead, Read};
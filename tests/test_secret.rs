@@ -0,0 +1,168 @@
+#![deny(warnings, rust_2018_idioms)]
+
+use bytes::{BufMut, SecretBytes, SecretBytesMut};
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::slice;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const LONG: &'static [u8] = b"mary had a little lamb, little lamb, little lamb";
+
+// Markers that the allocator below looks for in freed memory.
+const SECRET: &'static [u8] = b"correct horse battery staple";
+const CONTROL: &'static [u8] = b"tr0ub4dor&3 is not a secret";
+
+static SECRET_FREED: AtomicBool = AtomicBool::new(false);
+static CONTROL_FREED: AtomicBool = AtomicBool::new(false);
+
+// Records whether a marker was still in memory when it was freed.
+struct Scanning;
+
+unsafe impl GlobalAlloc for Scanning {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let block = slice::from_raw_parts(ptr, layout.size());
+
+        if block.windows(SECRET.len()).any(|w| w == SECRET) {
+            SECRET_FREED.store(true, Ordering::SeqCst);
+        }
+
+        if block.windows(CONTROL.len()).any(|w| w == CONTROL) {
+            CONTROL_FREED.store(true, Ordering::SeqCst);
+        }
+
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOC: Scanning = Scanning;
+
+fn is_sync<T: Sync>() {}
+fn is_send<T: Send>() {}
+
+#[test]
+fn test_bounds() {
+    is_sync::<SecretBytes>();
+    is_sync::<SecretBytesMut>();
+    is_send::<SecretBytes>();
+    is_send::<SecretBytesMut>();
+}
+
+#[test]
+fn fmt_is_redacted() {
+    let buf = SecretBytesMut::from(&b"hunter2"[..]);
+    assert_eq!("SecretBytesMut([REDACTED])", format!("{:?}", buf));
+
+    let buf = buf.freeze();
+    assert_eq!("SecretBytes([REDACTED])", format!("{:?}", buf));
+}
+
+#[test]
+fn never_inline() {
+    let mut buf = SecretBytesMut::with_capacity(4);
+    assert_eq!(buf.capacity(), 4);
+
+    buf.put(&b"abcd"[..]);
+    let ptr = buf.as_ptr();
+
+    let a = buf.split_to(2);
+    assert_eq!(a.as_ptr(), ptr);
+    assert_eq!(&a[..], b"ab");
+    assert_eq!(&buf[..], b"cd");
+
+    let b = a.freeze();
+    assert_eq!(b.slice(1..).as_ptr(), ptr.wrapping_add(1));
+}
+
+#[test]
+fn reserve_copies() {
+    let mut buf = SecretBytesMut::from(LONG);
+    let ptr = buf.as_ptr();
+
+    buf.reserve(0);
+    assert_eq!(buf.as_ptr(), ptr);
+
+    buf.reserve(10);
+    assert!(buf.capacity() >= LONG.len() + 10);
+    assert_eq!(&buf[..], LONG);
+
+    buf.extend_from_slice(b"!!!");
+    assert_eq!(&buf[LONG.len()..], b"!!!");
+}
+
+#[test]
+fn buf_mut_grows() {
+    let mut buf = SecretBytesMut::new();
+
+    for &b in LONG {
+        buf.put_u8(b);
+    }
+
+    buf.put_u32_le(0x01020304);
+    buf.put(&b"xyz"[..]);
+
+    assert_eq!(&buf[..LONG.len()], LONG);
+    assert_eq!(&buf[LONG.len()..], b"\x04\x03\x02\x01xyz");
+}
+
+#[test]
+fn split_and_unsplit() {
+    let mut buf = SecretBytesMut::with_capacity(64);
+    buf.extend_from_slice(LONG);
+
+    let ptr = buf.as_ptr();
+    let tail = buf.split_off(10);
+    assert_eq!(&buf[..], &LONG[..10]);
+
+    buf.unsplit(tail);
+    assert_eq!(&buf[..], LONG);
+    assert_eq!(buf.as_ptr(), ptr);
+
+    // Not contiguous, the data is copied
+    let mut a = SecretBytesMut::from(&b"hello "[..]);
+    a.unsplit(SecretBytesMut::from(&b"world"[..]));
+    assert_eq!(&a[..], b"hello world");
+}
+
+#[test]
+fn secret_bytes_slicing() {
+    let mut a = SecretBytes::from(LONG);
+    let b = a.split_off(20);
+    let c = a.split_to(5);
+
+    assert_eq!(&c[..], &LONG[..5]);
+    assert_eq!(&a[..], &LONG[5..20]);
+    assert_eq!(&b[..], &LONG[20..]);
+
+    assert_eq!(&b.slice(2..=4)[..], &LONG[22..25]);
+    assert!(b.slice(3..3).is_empty());
+
+    a.truncate(3);
+    assert_eq!(&a[..], &LONG[5..8]);
+    a.clear();
+    assert!(a.is_empty());
+}
+
+#[test]
+fn zeroed_before_free() {
+    let mut buf = SecretBytesMut::with_capacity(SECRET.len());
+    buf.put(SECRET);
+    let ptr = buf.as_ptr();
+
+    // Moves the data to a new allocation and frees the old one.
+    buf.reserve(1024);
+    assert_ne!(buf.as_ptr(), ptr);
+    assert_eq!(&buf[..], SECRET);
+    assert!(!SECRET_FREED.load(Ordering::SeqCst));
+
+    drop(buf);
+    assert!(!SECRET_FREED.load(Ordering::SeqCst));
+
+    // The allocator does see data that is freed without being zeroed.
+    drop(CONTROL.to_vec());
+    assert!(CONTROL_FREED.load(Ordering::SeqCst));
+}
@@ -1,20 +1,145 @@
 #![cfg(feature = "serde")]
 #![deny(warnings, rust_2018_idioms)]
 
-use serde_test::{Token, assert_tokens};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_test::{Configure, Token, assert_de_tokens, assert_de_tokens_error, assert_tokens};
 
 #[test]
 fn test_ser_de_empty() {
     let b = bytes::Bytes::new();
-    assert_tokens(&b, &[Token::Bytes(b"")]);
+    assert_tokens(&b.clone().compact(), &[Token::Bytes(b"")]);
+    assert_tokens(&b.readable(), &[Token::Str("")]);
     let b = bytes::BytesMut::with_capacity(0);
-    assert_tokens(&b, &[Token::Bytes(b"")]);
+    assert_tokens(&b.clone().compact(), &[Token::Bytes(b"")]);
+    assert_tokens(&b.readable(), &[Token::Str("")]);
 }
 
 #[test]
 fn test_ser_de() {
     let b = bytes::Bytes::from(&b"bytes"[..]);
-    assert_tokens(&b, &[Token::Bytes(b"bytes")]);
+    assert_tokens(&b.compact(), &[Token::Bytes(b"bytes")]);
     let b = bytes::BytesMut::from(&b"bytes"[..]);
-    assert_tokens(&b, &[Token::Bytes(b"bytes")]);
+    assert_tokens(&b.compact(), &[Token::Bytes(b"bytes")]);
+}
+
+#[test]
+fn test_ser_de_readable() {
+    let b = bytes::Bytes::from(&b"\xfb\xffbytes"[..]);
+    assert_tokens(&b.readable(), &[Token::Str("+/9ieXRlcw==")]);
+    let b = bytes::BytesMut::from(&b"\xfb\xffbytes"[..]);
+    assert_tokens(&b.readable(), &[Token::Str("+/9ieXRlcw==")]);
+
+    // Longer than one encoded chunk
+    let b = bytes::Bytes::from(&b"mary had a little lamb, little lamb, little lamb, its fleece was white as snow!"[..]);
+    assert_tokens(&b.readable(), &[
+        Token::Str("bWFyeSBoYWQgYSBsaXR0bGUgbGFtYiwgbGl0dGxlIGxhbWIsIGxpdHRsZSBsYW1iLCBpdHMgZmxlZWNlIHdhcyB3aGl0ZSBhcyBzbm93IQ=="),
+    ]);
+}
+
+#[test]
+fn test_de_readable_accepts_other_forms() {
+    let b = bytes::Bytes::from(&b"\xfb\xffbytes"[..]);
+
+    // URL-safe and unpadded base64
+    assert_de_tokens(&b.clone().readable(), &[Token::Str("-_9ieXRlcw")]);
+    // Byte arrays and sequences of integers
+    assert_de_tokens(&b.clone().readable(), &[Token::Bytes(b"\xfb\xffbytes")]);
+    assert_de_tokens(&bytes::Bytes::from(&b"\x01\x02"[..]).readable(), &[
+        Token::Seq { len: Some(2) },
+        Token::U8(1),
+        Token::U8(2),
+        Token::SeqEnd,
+    ]);
+    // Hex, as long as the string is not valid base64
+    assert_de_tokens(&bytes::Bytes::from(&b"\xfb\xff"[..]).readable(), &[Token::Str("0xfbff")]);
+}
+
+#[test]
+fn test_ser_de_readable_hex_like() {
+    // Base64 output that looks like a `0x` prefixed hex string
+    let b = bytes::Bytes::from(vec![0xd3, 0x10, 0x00]);
+    assert_tokens(&b.readable(), &[Token::Str("0xAA")]);
+}
+
+#[test]
+fn test_de_readable_invalid() {
+    assert_de_tokens_error::<serde_test::Readable<bytes::Bytes>>(
+        &[Token::Str("not base64!")],
+        concat!(
+            "invalid value: string \"not base64!\", ",
+            "expected a base64 string, 0x-prefixed hex string or byte array",
+        ),
+    );
+    assert_de_tokens_error::<serde_test::Readable<bytes::Bytes>>(
+        &[Token::Str("0xabc")],
+        concat!(
+            "invalid value: string \"0xabc\", ",
+            "expected a base64 string, 0x-prefixed hex string or byte array",
+        ),
+    );
+}
+
+#[derive(Debug, PartialEq)]
+struct Hash(bytes::Bytes);
+
+impl Serialize for Hash {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        bytes::serde::hex::serialize(&self.0, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Hash {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Hash, D::Error> {
+        bytes::serde::hex::deserialize(deserializer).map(Hash)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Token64(Vec<u8>);
+
+impl Serialize for Token64 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        bytes::serde::base64_url::serialize(&self.0, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Token64 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Token64, D::Error> {
+        bytes::serde::base64_url::deserialize(deserializer).map(Token64)
+    }
+}
+
+#[test]
+fn test_with_hex() {
+    let b = bytes::Bytes::from_static(b"\xde\xad\xbe\xef");
+
+    assert_tokens(&Hash(b.clone()).readable(), &[Token::Str("deadbeef")]);
+    assert_tokens(&Hash(b.clone()).compact(), &[Token::Bytes(b"\xde\xad\xbe\xef")]);
+    assert_de_tokens(&Hash(b).readable(), &[Token::Str("0xDEADBEEF")]);
+
+    assert_de_tokens_error::<serde_test::Readable<Hash>>(
+        &[Token::Str("3q2+7w==")],
+        "invalid value: string \"3q2+7w==\", expected a hex string or byte array",
+    );
+}
+
+#[test]
+fn test_with_base64_url() {
+    assert_tokens(&Token64(b"\xfb\xff".to_vec()).readable(), &[Token::Str("-_8")]);
+    assert_tokens(&Token64(b"\xfb\xff".to_vec()).compact(), &[Token::Bytes(b"\xfb\xff")]);
+    assert_de_tokens(&Token64(b"\xfb\xff".to_vec()).readable(), &[Token::Str("-_8=")]);
+}
+
+#[test]
+fn test_ser_de_byte_str() {
+    let s = bytes::ByteStr::from_static("hello");
+    assert_tokens(&s, &[Token::Str("hello")]);
+}
+
+#[test]
+fn test_de_byte_str_invalid_utf8() {
+    assert_de_tokens_error::<bytes::ByteStr>(
+        &[Token::Bytes(b"\xff")],
+        "invalid value: byte array, expected a string",
+    );
 }
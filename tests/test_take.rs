@@ -10,3 +10,41 @@ fn long_take() {
     assert_eq!(11, buf.remaining());
     assert_eq!(b"hello world", buf.bytes());
 }
+
+#[test]
+fn peek_within_limit() {
+    let buf = (&b"\x01\x02\x03\x04"[..]).chain(&b"\x05\x06"[..]).take(5);
+
+    assert_eq!(0x01020304, buf.peek_u32());
+
+    let mut dst = [0; 5];
+    buf.peek_slice(&mut dst);
+    assert_eq!(&dst, b"\x01\x02\x03\x04\x05");
+}
+
+#[test]
+#[should_panic]
+fn peek_past_limit() {
+    let buf = b"hello world".take(3);
+    buf.peek_u32();
+}
+
+#[test]
+fn copy_to_bytes() {
+    use bytes::Bytes;
+
+    let data = Bytes::from(&[7; 64][..]);
+    let mut buf = data.clone().take(48);
+
+    let a = buf.copy_to_bytes(40);
+    assert_eq!(a.as_ptr(), data.as_ptr());
+    assert_eq!(buf.remaining(), 8);
+    assert_eq!(buf.get_ref().len(), 24);
+}
+
+#[test]
+#[should_panic]
+fn copy_to_bytes_past_limit() {
+    let mut buf = (&b"hello world"[..]).take(3);
+    buf.copy_to_bytes(4);
+}